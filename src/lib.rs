@@ -1,280 +1,1086 @@
+// the pyo3 0.18 macros expand to impl blocks inside of consts
+#![allow(non_local_definitions)]
+
 use pyo3::prelude::*;
+use pyo3::basic::CompareOp;
+use pyo3::types::{PyDict, PyIterator, PyList, PySlice, PyTuple};
+use chrono::NaiveDate;
 
 mod vec_data;
 mod vec_operations;
 mod vec_comparisons;
 mod vec_logic;
 mod vec_index;
+mod vec_error;
 
 mod string_methods;
+mod math_methods;
+mod vec_aggregate;
+mod vec_cumulative;
+mod vec_sort;
+mod vec_sets;
+mod vec_table;
+mod vec_seq;
+mod vec_utils;
+mod vec_factor;
+mod vec_date;
+mod vec_datetime;
+mod vec_duration;
+mod vec_complex;
 
-/// default percision
+/// default percision, 64 bit like R's doubles.
+/// building with the `precision32` feature stores 32 bit values instead
+#[cfg(not(feature = "precision32"))]
+type Fdef = f64;
+#[cfg(not(feature = "precision32"))]
+type Idef = i64;
+#[cfg(feature = "precision32")]
 type Fdef = f32;
+#[cfg(feature = "precision32")]
 type Idef = i32;
 
-use vec_data::{RVecData, from_py, BaseRVecData};
+use vec_data::{RVecData, from_py, element_strings, BaseRVecData};
+use vec_operations::ElementArith;
 use vec_comparisons::ElementCmp;
 use vec_logic::ElementLogic;
-use vec_index::Indexing;
+use vec_index::{Indexing, NegativeIndex, name_positions, slice_positions};
 use string_methods::VecStringMethods;
+use math_methods::VecMathMethods;
+use vec_aggregate::VecAggregate;
+use vec_cumulative::VecCumulative;
+use vec_sort::{NaRank, TiesMethod, VecSort};
+use vec_sets::VecSets;
+use vec_error::RVecError;
+use vec_datetime::{Component, Datetime};
+use vec_duration::Duration;
+use vec_complex::Part;
+
+impl From<RVecError> for PyErr {
+    fn from(err: RVecError) -> PyErr {
+        match err {
+            RVecError::TypeMismatch { .. } | RVecError::UnsupportedType { .. } | RVecError::Conversion(_) => {
+                pyo3::exceptions::PyTypeError::new_err(err.to_string())
+            }
+            RVecError::LengthMismatch { .. } | RVecError::InvalidArgument(_) => {
+                pyo3::exceptions::PyValueError::new_err(err.to_string())
+            }
+            RVecError::IndexOutOfRange { .. } | RVecError::InvalidIndex(_) => {
+                pyo3::exceptions::PyIndexError::new_err(err.to_string())
+            }
+        }
+    }
+}
 
 #[pyclass]
 #[derive(Clone, Debug)]
 struct RVec {
     data: RVecData,
+    /// optional names of the elements, always RVecData::Str
+    names: Option<RVecData>,
 }
 
 #[pymethods]
 impl RVec {
+    /// Creates an RVec from a python value, list or dict. the keys of a dict become the names
     #[new]
-    pub fn new(data: &PyAny) -> PyResult<Self> {
-        Ok(RVec { data: from_py(data)? })
+    #[pyo3(signature = (data, names = None))]
+    pub fn new(data: &PyAny, names: Option<&PyAny>) -> PyResult<Self> {
+        let mut vec = if let Ok(dict) = data.downcast::<PyDict>() {
+            RVec { data: from_py(dict.values())?, names: Some(from_py(dict.keys())?.as_str()) }
+        } else {
+            RVec { data: from_py(data)?, names: None }
+        };
+        if names.is_some() {
+            vec.set_names(names)?;
+        }
+        Ok(vec)
+    }
+
+    #[getter]
+    pub fn names(&self) -> PyResult<Option<Vec<PyObject>>> {
+        self.names.as_ref().map(|names| names.to_list()).transpose()
+    }
+
+    /// Sets the names of the elements, None removes them
+    #[setter]
+    pub fn set_names(&mut self, names: Option<&PyAny>) -> PyResult<()> {
+        self.names = match names {
+            Some(names) => {
+                let names = from_py(names)?.as_str();
+                if names.len() != self.data.len() {
+                    return Err(RVecError::LengthMismatch { lhs: self.data.len(), rhs: names.len() }.into());
+                }
+                Some(names)
+            }
+            None => None,
+        };
+        Ok(())
     }
 
     #[pyo3(text_signature = "($self, /)")]
     pub fn str(&self) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str() })
+        Ok(self.with_data(self.data.as_str()))
+    }
+
+    /// Converts to ints, factors give their codes from 1 like R
+    #[pyo3(text_signature = "($self, /)")]
+    pub fn int(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.as_int()))
+    }
+
+    #[pyo3(text_signature = "($self, /)")]
+    pub fn float(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.as_float()))
+    }
+
+    /// Converts to complex numbers, strings are read like "1+2i"
+    #[pyo3(text_signature = "($self, /)")]
+    pub fn complex(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.as_complex()))
+    }
+
+    /// The levels of a factor, None for other vectors
+    #[getter]
+    pub fn levels(&self) -> Option<Vec<String>> {
+        match &self.data {
+            RVecData::Factor(f) => Some(f.levels.clone()),
+            _ => None,
+        }
+    }
+
+    /// Removes the levels of a factor that no element has
+    pub fn droplevels(&self) -> PyResult<Self> {
+        match &self.data {
+            RVecData::Factor(f) => Ok(self.with_data(RVecData::Factor(vec_factor::droplevels(f)))),
+            x => Err(RVecError::UnsupportedType { op: "droplevels()", dtype: x.element_type() }.into()),
+        }
+    }
+
+    /// Moves the level `reference` of an unordered factor first
+    pub fn relevel(&self, reference: &str) -> PyResult<Self> {
+        match &self.data {
+            RVecData::Factor(f) => Ok(self.with_data(RVecData::Factor(vec_factor::relevel(f, reference)?))),
+            x => Err(RVecError::UnsupportedType { op: "relevel()", dtype: x.element_type() }.into()),
+        }
+    }
+
+    /// Formats dates or datetimes as strings with a strftime format like R's format.Date,
+    /// ISO 8601 by default
+    #[pyo3(signature = (format = None))]
+    pub fn format(&self, format: Option<&str>) -> PyResult<Self> {
+        match &self.data {
+            RVecData::Date(a) => Ok(self.with_data(RVecData::Str(vec_date::format_dates(a, format.unwrap_or(vec_date::ISO_DATE))?))),
+            RVecData::Datetime(d) => Ok(self.with_data(RVecData::Str(d.format(format.unwrap_or(vec_datetime::ISO_DATETIME))?))),
+            x => Err(RVecError::UnsupportedType { op: "format()", dtype: x.element_type() }.into()),
+        }
+    }
+
+    /// The UTC offset datetimes are shown in like "+01:00", None for other vectors
+    #[getter]
+    pub fn utc_offset(&self) -> Option<String> {
+        match &self.data {
+            RVecData::Datetime(d) => Some(d.offset.to_string()),
+            _ => None,
+        }
+    }
+
+    /// The same times shown in another UTC offset, like "+05:30" or "UTC"
+    pub fn with_offset(&self, offset: &str) -> PyResult<Self> {
+        match &self.data {
+            RVecData::Datetime(d) => Ok(self.with_data(RVecData::Datetime(Datetime { offset: vec_datetime::parse_offset(offset)?, ..d.clone() }))),
+            x => Err(RVecError::UnsupportedType { op: "with_offset()", dtype: x.element_type() }.into()),
+        }
+    }
+
+    /// The units of a duration like "hours", None for other vectors
+    #[getter]
+    pub fn units(&self) -> Option<&'static str> {
+        match &self.data {
+            RVecData::Duration(d) => Some(vec_duration::unit_name(d.units)),
+            _ => None,
+        }
+    }
+
+    /// Converts a duration to other units, the lengths stay the same like R's `units<-`
+    #[setter]
+    pub fn set_units(&mut self, units: &str) -> PyResult<()> {
+        match &self.data {
+            RVecData::Duration(d) => self.data = RVecData::Duration(d.to_units(vec_duration::parse_duration_units(units)?)),
+            x => return Err(RVecError::UnsupportedType { op: "units", dtype: x.element_type() }.into()),
+        }
+        Ok(())
+    }
+
+    pub fn year(&self) -> PyResult<Self> {
+        Ok(self.with_data(vec_datetime::components(&self.data, Component::Year)?))
+    }
+
+    pub fn month(&self) -> PyResult<Self> {
+        Ok(self.with_data(vec_datetime::components(&self.data, Component::Month)?))
+    }
+
+    pub fn day(&self) -> PyResult<Self> {
+        Ok(self.with_data(vec_datetime::components(&self.data, Component::Day)?))
+    }
+
+    pub fn hour(&self) -> PyResult<Self> {
+        Ok(self.with_data(vec_datetime::components(&self.data, Component::Hour)?))
+    }
+
+    pub fn minute(&self) -> PyResult<Self> {
+        Ok(self.with_data(vec_datetime::components(&self.data, Component::Minute)?))
+    }
+
+    /// The whole seconds of the minute
+    pub fn second(&self) -> PyResult<Self> {
+        Ok(self.with_data(vec_datetime::components(&self.data, Component::Second)?))
+    }
+
+    /// The day of the week from 1 for monday to 7 for sunday
+    pub fn weekday(&self) -> PyResult<Self> {
+        Ok(self.with_data(vec_datetime::components(&self.data, Component::Weekday)?))
+    }
+
+    /// The ISO 8601 week of the year
+    pub fn isoweek(&self) -> PyResult<Self> {
+        Ok(self.with_data(vec_datetime::components(&self.data, Component::IsoWeek)?))
     }
 
     pub fn to_list(&self) -> PyResult<Vec<PyObject>> {
         self.data.to_list()
     }
 
+    #[pyo3(text_signature = "($self, /)")]
+    pub fn is_na(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.is_na()))
+    }
+
     pub fn __repr__(&self) -> PyResult<String> {
-        Ok(format!("{:?}", self))
+        Ok(format!("RVec({})", self.__str__()?))
     }
 
+    /// Formats like a python list, named elements are written as name=value like R's c()
     pub fn __str__(&self) -> PyResult<String> {
-        Ok(format!("{:?}", self.data))
+        match &self.names {
+            Some(RVecData::Str(names)) => {
+                let elements = names.iter().zip(element_strings(&self.data))
+                    .map(|(name, x)| format!("{}={}", name.as_deref().unwrap_or("<NA>"), x))
+                    .collect::<Vec<_>>();
+                Ok(format!("[{}]", elements.join(", ")))
+            }
+            _ => Ok(format!("{}", self.data)),
+        }
     }
 
     pub fn __len__(&self) -> PyResult<usize> {
         Ok(self.data.len())
     }
 
+    pub fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyIterator> {
+        PyIterator::from_object(py, PyList::new(py, self.data.to_list()?))
+    }
+
     pub fn __add__(&self, other: &PyAny) -> PyResult<Self> {
-        if let Ok(other) = other.extract::<RVec>() {
-            Ok(RVec { data: self.data.clone() + other.data.clone() })
-        } else {
-            Ok(RVec { data: self.data.clone() + from_py(&other)? })
-        }
+        let other = RVec::operand(other)?;
+        Ok(self.binary(&other, (self.data.clone() + other.data.clone())?))
     }
 
     pub fn __radd__(&self, other: &PyAny) -> PyResult<Self> {
-        if let Ok(other) = other.extract::<RVec>() {
-            Ok(RVec { data: other.data.clone() + self.data.clone() })
-        } else {
-            Ok(RVec { data: from_py(&other)? + self.data.clone() })
-        }
+        let other = RVec::operand(other)?;
+        Ok(other.binary(self, (other.data.clone() + self.data.clone())?))
     }
 
     pub fn __sub__(&self, other: &PyAny) -> PyResult<Self> {
-        if let Ok(other) = other.extract::<RVec>() {
-            Ok(RVec { data: self.data.clone() - other.data.clone() })
-        } else {
-            Ok(RVec { data: self.data.clone() - from_py(&other)? })
-        }
+        let other = RVec::operand(other)?;
+        Ok(self.binary(&other, (self.data.clone() - other.data.clone())?))
     }
 
     pub fn __rsub__(&self, other: &PyAny) -> PyResult<Self> {
-        if let Ok(other) = other.extract::<RVec>() {
-            Ok(RVec { data: other.data.clone() - self.data.clone() })
-        } else {
-            Ok(RVec { data: from_py(&other)? - self.data.clone() })
-        }
+        let other = RVec::operand(other)?;
+        Ok(other.binary(self, (other.data.clone() - self.data.clone())?))
     }
 
     pub fn __mul__(&self, other: &PyAny) -> PyResult<Self> {
-        if let Ok(other) = other.extract::<RVec>() {
-            Ok(RVec { data: self.data.clone() * other.data.clone() })
-        } else {
-            Ok(RVec { data: self.data.clone() * from_py(&other)? })
-        }
+        let other = RVec::operand(other)?;
+        Ok(self.binary(&other, (self.data.clone() * other.data.clone())?))
     }
 
     pub fn __rmul__(&self, other: &PyAny) -> PyResult<Self> {
-        if let Ok(other) = other.extract::<RVec>() {
-            Ok(RVec { data: other.data.clone() * self.data.clone() })
-        } else {
-            Ok(RVec { data: from_py(&other)? * self.data.clone() })
-        }
+        let other = RVec::operand(other)?;
+        Ok(other.binary(self, (other.data.clone() * self.data.clone())?))
     }
 
     pub fn __truediv__(&self, other: &PyAny) -> PyResult<Self> {
-        if let Ok(other) = other.extract::<RVec>() {
-            Ok(RVec { data: self.data.clone() / other.data.clone() })
-        } else {
-            Ok(RVec { data: self.data.clone() / from_py(&other)? })
-        }
+        let other = RVec::operand(other)?;
+        Ok(self.binary(&other, (self.data.clone() / other.data.clone())?))
     }
 
     pub fn __rtruediv__(&self, other: &PyAny) -> PyResult<Self> {
-        if let Ok(other) = other.extract::<RVec>() {
-            Ok(RVec { data: other.data.clone() / self.data.clone() })
-        } else {
-            Ok(RVec { data: from_py(&other)? / self.data.clone() })
+        let other = RVec::operand(other)?;
+        Ok(other.binary(self, (other.data.clone() / self.data.clone())?))
+    }
+
+    // pow() with a modulus isn't supported, rather than ignoring it
+    pub fn __pow__(&self, other: &PyAny, modulo: Option<&PyAny>) -> PyResult<Self> {
+        if modulo.is_some_and(|m| !m.is_none()) {
+            return Err(RVecError::UnsupportedType { op: "pow() with a modulus", dtype: self.data.element_type() }.into());
+        }
+        let other = RVec::operand(other)?;
+        Ok(self.binary(&other, self.data.pow_ew(&other.data)?))
+    }
+
+    pub fn __rpow__(&self, other: &PyAny, modulo: Option<&PyAny>) -> PyResult<Self> {
+        if modulo.is_some_and(|m| !m.is_none()) {
+            return Err(RVecError::UnsupportedType { op: "pow() with a modulus", dtype: self.data.element_type() }.into());
         }
+        let other = RVec::operand(other)?;
+        Ok(other.binary(self, other.data.pow_ew(&self.data)?))
+    }
+
+    pub fn __floordiv__(&self, other: &PyAny) -> PyResult<Self> {
+        let other = RVec::operand(other)?;
+        Ok(self.binary(&other, self.data.floordiv_ew(&other.data)?))
+    }
+
+    pub fn __rfloordiv__(&self, other: &PyAny) -> PyResult<Self> {
+        let other = RVec::operand(other)?;
+        Ok(other.binary(self, other.data.floordiv_ew(&self.data)?))
+    }
+
+    pub fn __mod__(&self, other: &PyAny) -> PyResult<Self> {
+        let other = RVec::operand(other)?;
+        Ok(self.binary(&other, (self.data.clone() % other.data.clone())?))
+    }
+
+    pub fn __rmod__(&self, other: &PyAny) -> PyResult<Self> {
+        let other = RVec::operand(other)?;
+        Ok(other.binary(self, (other.data.clone() % self.data.clone())?))
+    }
+
+    pub fn __divmod__(&self, other: &PyAny) -> PyResult<(Self, Self)> {
+        Ok((self.__floordiv__(other)?, self.__mod__(other)?))
+    }
+
+    pub fn __rdivmod__(&self, other: &PyAny) -> PyResult<(Self, Self)> {
+        Ok((self.__rfloordiv__(other)?, self.__rmod__(other)?))
     }
 
     pub fn __neg__(&self) -> PyResult<Self> {
-        Ok(RVec { data: -self.data.clone() })
+        Ok(self.with_data((-self.data.clone())?))
     }
 
     pub fn __pos__(&self) -> PyResult<Self> {
-        Ok(RVec { data: self.data.clone() })
+        Ok(self.with_data(self.data.clone()))
     }
 
     pub fn __abs__(&self) -> PyResult<Self> {
-        panic!("Not implemented") // TODO
-    }
-
-    // TODO: comparisons are not working
-    // they work when calling them directly, but not when using the operators
-    
-    // pub fn __eq__(&self, other: &PyAny) -> PyResult<Self> {
-    //     if let Ok(other) = other.extract::<RVec>() {
-    //         Ok(RVec { data: self.data.eq_ew(&other.data) })
-    //     } else {
-    //         Ok(RVec { data: self.data.eq_ew(&from_py(&other)?) })
-    //     }
-    // }
-
-    // pub fn __ne__(&self, other: &PyAny) -> PyResult<Self> {
-    //     if let Ok(other) = other.extract::<RVec>() {
-    //         Ok(RVec { data: self.data.ne_ew(&other.data) })
-    //     } else {
-    //         Ok(RVec { data: self.data.ne_ew(&from_py(&other)?) })
-    //     }
-    // }
-
-    // pub fn __lt__(&self, other: &PyAny) -> PyResult<Self> {
-    //     if let Ok(other) = other.extract::<RVec>() {
-    //         Ok(RVec { data: self.data.lt_ew(&other.data) })
-    //     } else {
-    //         Ok(RVec { data: self.data.lt_ew(&from_py(&other)?) })
-    //     }
-    // }
-
-    // pub fn __le__(&self, other: &PyAny) -> PyResult<Self> {
-    //     if let Ok(other) = other.extract::<RVec>() {
-    //         Ok(RVec { data: self.data.le_ew(&other.data) })
-    //     } else {
-    //         Ok(RVec { data: self.data.le_ew(&from_py(&other)?) })
-    //     }
-    // }
-
-    // pub fn __gt__(&self, other: &PyAny) -> PyResult<Self> {
-    //     if let Ok(other) = other.extract::<RVec>() {
-    //         Ok(RVec { data: self.data.gt_ew(&other.data) })
-    //     } else {
-    //         Ok(RVec { data: self.data.gt_ew(&from_py(&other)?) })
-    //     }
-    // }
-
-    // pub fn __ge__(&self, other: &PyAny) -> PyResult<Self> {
-    //     if let Ok(other) = other.extract::<RVec>() {
-    //         Ok(RVec { data: self.data.ge_ew(&other.data) })
-    //     } else {
-    //         Ok(RVec { data: self.data.ge_ew(&from_py(&other)?) })
-    //     }
-    // }
+        Ok(self.with_data(self.data.abs_ew()?))
+    }
+
+    pub fn __richcmp__(&self, other: &PyAny, op: CompareOp) -> PyResult<Self> {
+        let other = RVec::operand(other)?;
+        let data = match op {
+            CompareOp::Eq => self.data.eq_ew(&other.data)?,
+            CompareOp::Ne => self.data.ne_ew(&other.data)?,
+            CompareOp::Lt => self.data.lt_ew(&other.data)?,
+            CompareOp::Le => self.data.le_ew(&other.data)?,
+            CompareOp::Gt => self.data.gt_ew(&other.data)?,
+            CompareOp::Ge => self.data.ge_ew(&other.data)?,
+        };
+        Ok(self.binary(&other, data))
+    }
 
     pub fn __and__(&self, other: &PyAny) -> PyResult<Self> {
-        if let Ok(other) = other.extract::<RVec>() {
-            Ok(RVec { data: self.data.and_ew(&other.data) })
-        } else {
-            Ok(RVec { data: self.data.and_ew(&from_py(&other)?) })
-        }
+        let other = RVec::operand(other)?;
+        Ok(self.binary(&other, self.data.and_ew(&other.data)?))
     }
 
     pub fn __or__(&self, other: &PyAny) -> PyResult<Self> {
-        if let Ok(other) = other.extract::<RVec>() {
-            Ok(RVec { data: self.data.or_ew(&other.data) })
-        } else {
-            Ok(RVec { data: self.data.or_ew(&from_py(&other)?) })
-        }
+        let other = RVec::operand(other)?;
+        Ok(self.binary(&other, self.data.or_ew(&other.data)?))
     }
 
     pub fn __xor__(&self, other: &PyAny) -> PyResult<Self> {
-        if let Ok(other) = other.extract::<RVec>() {
-            Ok(RVec { data: self.data.xor_ew(&other.data) })
-        } else {
-            Ok(RVec { data: self.data.xor_ew(&from_py(&other)?) })
-        }
+        let other = RVec::operand(other)?;
+        Ok(self.binary(&other, self.data.xor_ew(&other.data)?))
     }
 
     pub fn __invert__(&self) -> PyResult<Self> {
-        Ok(RVec { data: self.data.not_ew() })
+        Ok(self.with_data(self.data.not_ew()?))
     }
 
     pub fn __getitem__(&self, index: &PyAny) -> PyResult<Self> {
-        if let Ok(index) = index.extract::<RVec>() {
-            Ok(RVec { data: self.data.getindex(index.data) })
-        } else {
-            Ok(RVec { data: self.data.getindex(from_py(&index)?) })
-        }
+        let index = self.index_data(index, false)?;
+        self.take(index)
     }
 
     pub fn __setitem__(&mut self, index: &PyAny, value: &PyAny) -> PyResult<()> {
-        panic!("Not implemented {} {}", index, value) // TODO
+        let index = self.index_data(index, true)?;
+        Ok(self.data.setindex(index, RVec::operand(value)?.data)?)
+    }
+
+    pub fn sqrt(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.sqrt()?))
+    }
+
+    pub fn exp(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.exp()?))
+    }
+
+    /// The natural log, or the log in `base`
+    #[pyo3(signature = (base = None))]
+    pub fn log(&self, base: Option<f64>) -> PyResult<Self> {
+        Ok(self.with_data(self.data.log(base)?))
+    }
+
+    pub fn log1p(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.log1p()?))
+    }
+
+    pub fn expm1(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.expm1()?))
+    }
+
+    pub fn sin(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.sin()?))
+    }
+
+    pub fn cos(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.cos()?))
+    }
+
+    pub fn tan(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.tan()?))
+    }
+
+    pub fn asin(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.asin()?))
+    }
+
+    pub fn acos(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.acos()?))
+    }
+
+    pub fn atan(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.atan()?))
+    }
+
+    pub fn sinh(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.sinh()?))
+    }
+
+    pub fn cosh(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.cosh()?))
+    }
+
+    pub fn tanh(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.tanh()?))
+    }
+
+    pub fn asinh(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.asinh()?))
+    }
+
+    pub fn acosh(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.acosh()?))
+    }
+
+    pub fn atanh(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.atanh()?))
+    }
+
+    pub fn floor(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.floor()?))
+    }
+
+    pub fn ceiling(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.ceiling()?))
+    }
+
+    /// Truncates numbers toward zero, or dates and datetimes down to the start of their
+    /// `units` ("secs", "mins", "hours", "days", "weeks", "months" or "years")
+    #[pyo3(signature = (units = None))]
+    pub fn trunc(&self, units: Option<&str>) -> PyResult<Self> {
+        match (&self.data, units) {
+            (RVecData::Date(_) | RVecData::Datetime(_), units) => {
+                let units = vec_datetime::parse_unit(units.unwrap_or("secs"))?;
+                Ok(self.with_data(vec_datetime::trunc_time(&self.data, units, false)?))
+            }
+            (x, Some(_)) => Err(RVecError::UnsupportedType { op: "trunc() with units", dtype: x.element_type() }.into()),
+            (x, None) => Ok(self.with_data(x.trunc()?)),
+        }
+    }
+
+    /// Rounds numbers to `digits` decimal places, or dates and datetimes to the nearest start
+    /// of their `units` like trunc
+    #[pyo3(signature = (digits = 0, units = None))]
+    pub fn round(&self, digits: i32, units: Option<&str>) -> PyResult<Self> {
+        match (&self.data, units) {
+            (RVecData::Date(_) | RVecData::Datetime(_), units) => {
+                let units = vec_datetime::parse_unit(units.unwrap_or("secs"))?;
+                Ok(self.with_data(vec_datetime::trunc_time(&self.data, units, true)?))
+            }
+            (x, Some(_)) => Err(RVecError::UnsupportedType { op: "round() with units", dtype: x.element_type() }.into()),
+            (x, None) => Ok(self.with_data(x.round(digits)?)),
+        }
+    }
+
+    #[pyo3(signature = (digits = 6))]
+    pub fn signif(&self, digits: i32) -> PyResult<Self> {
+        Ok(self.with_data(self.data.signif(digits)?))
+    }
+
+    #[pyo3(signature = (na_rm = false))]
+    pub fn sum(&self, na_rm: bool) -> PyResult<Self> {
+        Ok(RVec { data: self.data.sum(na_rm)?, names: None })
+    }
+
+    #[pyo3(signature = (na_rm = false))]
+    pub fn prod(&self, na_rm: bool) -> PyResult<Self> {
+        Ok(RVec { data: self.data.prod(na_rm)?, names: None })
+    }
+
+    #[pyo3(signature = (na_rm = false))]
+    pub fn mean(&self, na_rm: bool) -> PyResult<Self> {
+        Ok(RVec { data: self.data.mean(na_rm)?, names: None })
+    }
+
+    #[pyo3(signature = (na_rm = false))]
+    pub fn min(&self, na_rm: bool) -> PyResult<Self> {
+        Ok(RVec { data: self.data.min(na_rm)?, names: None })
+    }
+
+    #[pyo3(signature = (na_rm = false))]
+    pub fn max(&self, na_rm: bool) -> PyResult<Self> {
+        Ok(RVec { data: self.data.max(na_rm)?, names: None })
+    }
+
+    #[pyo3(signature = (na_rm = false))]
+    pub fn range(&self, na_rm: bool) -> PyResult<Self> {
+        Ok(RVec { data: self.data.range(na_rm)?, names: None })
+    }
+
+    #[pyo3(signature = (na_rm = false))]
+    pub fn var(&self, na_rm: bool) -> PyResult<Self> {
+        Ok(RVec { data: self.data.var(na_rm)?, names: None })
+    }
+
+    #[pyo3(signature = (na_rm = false))]
+    pub fn sd(&self, na_rm: bool) -> PyResult<Self> {
+        Ok(RVec { data: self.data.sd(na_rm)?, names: None })
+    }
+
+    #[pyo3(signature = (na_rm = false))]
+    pub fn median(&self, na_rm: bool) -> PyResult<Self> {
+        Ok(RVec { data: self.data.median(na_rm)?, names: None })
+    }
+
+    pub fn cumsum(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.cumsum()?))
+    }
+
+    pub fn cumprod(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.cumprod()?))
+    }
+
+    pub fn cummax(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.cummax()?))
+    }
+
+    pub fn cummin(&self) -> PyResult<Self> {
+        Ok(self.with_data(self.data.cummin()?))
+    }
+
+    /// Sorts the vector and its names, NA is dropped unless `na_last` is True or False
+    #[pyo3(signature = (decreasing = false, na_last = None))]
+    pub fn sort(&self, decreasing: bool, na_last: Option<bool>) -> PyResult<Self> {
+        self.take(self.data.order(decreasing, na_last)?)
+    }
+
+    /// The positions that sort the vector, for use as an index
+    #[pyo3(signature = (decreasing = false, na_last = true))]
+    pub fn order(&self, decreasing: bool, na_last: Option<bool>) -> PyResult<Self> {
+        Ok(RVec { data: self.data.order(decreasing, na_last)?, names: None })
+    }
+
+    /// The ranks of the elements from 1, `ties_method` is one of "average", "first",
+    /// "min", "max", "dense" or "random" like R. NA is ranked last like R, first if
+    /// `na_last` is False, or has rank NA if it is "keep"
+    #[pyo3(signature = (ties_method = "average", na_last = None))]
+    pub fn rank(&self, ties_method: &str, na_last: Option<&PyAny>) -> PyResult<Self> {
+        let na = match na_last {
+            None => NaRank::Last,
+            Some(x) if x.extract::<&str>().is_ok_and(|x| x == "keep") => NaRank::Keep,
+            Some(x) => match x.extract::<bool>() {
+                Ok(true) => NaRank::Last,
+                Ok(false) => NaRank::First,
+                Err(_) => return Err(pyo3::exceptions::PyValueError::new_err("na_last must be True, False or \"keep\"")),
+            },
+        };
+        let ties = match ties_method {
+            "average" => TiesMethod::Average,
+            "first" => TiesMethod::First,
+            "min" => TiesMethod::Min,
+            "max" => TiesMethod::Max,
+            "dense" => TiesMethod::Dense,
+            "random" => TiesMethod::Random,
+            _ => return Err(pyo3::exceptions::PyValueError::new_err(format!("unknown ties method {}", ties_method))),
+        };
+        Ok(self.with_data(self.data.rank(ties, na)?))
+    }
+
+    pub fn unique(&self) -> PyResult<Self> {
+        Ok(RVec { data: self.data.unique()?, names: None })
+    }
+
+    #[pyo3(signature = (from_last = false))]
+    pub fn duplicated(&self, from_last: bool) -> PyResult<Self> {
+        Ok(RVec { data: self.data.duplicated(from_last), names: None })
+    }
+
+    pub fn union(&self, other: &PyAny) -> PyResult<Self> {
+        Ok(RVec { data: self.data.union(&RVec::operand(other)?.data)?, names: None })
+    }
+
+    pub fn intersect(&self, other: &PyAny) -> PyResult<Self> {
+        Ok(RVec { data: self.data.intersect(&RVec::operand(other)?.data)?, names: None })
+    }
+
+    pub fn setdiff(&self, other: &PyAny) -> PyResult<Self> {
+        Ok(RVec { data: self.data.setdiff(&RVec::operand(other)?.data)?, names: None })
+    }
+
+    pub fn is_in(&self, table: &PyAny) -> PyResult<Self> {
+        Ok(RVec { data: self.data.is_in(&RVec::operand(table)?.data)?, names: None })
+    }
+
+    /// The position of each element in `table`, NA if it isn't there, R's match
+    pub fn match_in(&self, table: &PyAny) -> PyResult<Self> {
+        Ok(RVec { data: self.data.match_in(&RVec::operand(table)?.data)?, names: None })
+    }
+
+    /// How often each value occurs, named by the values. sorted by value like R,
+    /// or by decreasing count if `by_count`. NA is counted if `use_na`
+    #[pyo3(signature = (by_count = false, use_na = false))]
+    pub fn table(&self, by_count: bool, use_na: bool) -> PyResult<Self> {
+        let (levels, counts) = vec_table::table(&self.data, use_na, by_count)?;
+        Ok(RVec { data: counts, names: Some(levels.as_str()) })
+    }
+
+    /// How often each value occurs, most frequent first
+    #[pyo3(signature = (use_na = false))]
+    pub fn value_counts(&self, use_na: bool) -> PyResult<Self> {
+        self.table(true, use_na)
     }
 
     pub fn str_capitalize(&self) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str().capitalize() })
+        Ok(self.with_data(self.data.as_str().capitalize()?))
     }
 
     pub fn str_center(&self, width: usize, fill_char: char) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str().center(width, fill_char) })
+        Ok(self.with_data(self.data.as_str().center(width, fill_char)?))
     }
 
     pub fn str_count(&self, sub: &str) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str().count(sub) })
+        Ok(self.with_data(self.data.as_str().count(sub)?))
     }
 
     pub fn str_endswith(&self, suffix: &str) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str().endswith(suffix) })
+        Ok(self.with_data(self.data.as_str().endswith(suffix)?))
     }
 
     pub fn str_startswith(&self, prefix: &str) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str().startswith(prefix) })
+        Ok(self.with_data(self.data.as_str().startswith(prefix)?))
     }
 
     pub fn str_find(&self, sub: &str) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str().find(sub) })
+        Ok(self.with_data(self.data.as_str().find(sub)?))
     }
 
     pub fn str_lower(&self) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str().lower() })
+        Ok(self.with_data(self.data.as_str().lower()?))
     }
 
     pub fn str_upper(&self) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str().upper() })
+        Ok(self.with_data(self.data.as_str().upper()?))
     }
 
     pub fn str_replace(&self, old: &str, new: &str) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str().replace(old, new) })
+        Ok(self.with_data(self.data.as_str().replace(old, new)?))
     }
 
     pub fn str_split(&self, sep: &str) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str().split(sep) })
+        Ok(self.with_data(self.data.as_str().split(sep)?))
     }
 
     pub fn str_strip(&self) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str().strip() })
+        Ok(self.with_data(self.data.as_str().strip()?))
     }
 
     pub fn str_lstrip(&self) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str().lstrip() })
+        Ok(self.with_data(self.data.as_str().lstrip()?))
     }
 
     pub fn str_rstrip(&self) -> PyResult<Self> {
-        Ok(RVec { data: self.data.as_str().rstrip() })
+        Ok(self.with_data(self.data.as_str().rstrip()?))
+    }
+}
+
+impl RVec {
+    /// Converts the other operand of an operation to an RVec, python values have no names
+    fn operand(other: &PyAny) -> PyResult<RVec> {
+        if let Ok(other) = other.extract::<RVec>() {
+            Ok(other)
+        } else {
+            Ok(RVec { data: from_py(other)?, names: None })
+        }
+    }
+
+    /// Joins the vectors end to end in their common type. if any of them have names, the
+    /// elements without names get "" like R
+    fn concat(parts: &[RVec]) -> RVec {
+        let data = vec_utils::concat(&parts.iter().map(|x| &x.data).collect::<Vec<_>>());
+        if parts.iter().all(|x| x.names.is_none()) {
+            return RVec { data, names: None };
+        }
+        let names = parts.iter()
+            .map(|x| x.names.clone().unwrap_or_else(|| RVecData::Str(vec![Some(String::new()); x.data.len()])))
+            .collect::<Vec<_>>();
+        RVec { data, names: Some(vec_utils::concat(&names.iter().collect::<Vec<_>>())) }
+    }
+
+    /// Adds the RVecs in a python value to `parts`, lists and tuples are flattened
+    fn flatten(obj: &PyAny, parts: &mut Vec<RVec>) -> PyResult<()> {
+        if let Ok(x) = obj.extract::<RVec>() {
+            parts.push(x);
+        } else if obj.is_instance_of::<PyDict>()? {
+            parts.push(RVec::new(obj, None)?);
+        } else if obj.is_instance_of::<PyList>()? || obj.is_instance_of::<PyTuple>()? {
+            // lists of plain values are converted at once, the rest element by element
+            match from_py(obj) {
+                Ok(data) => parts.push(RVec { data, names: None }),
+                Err(_) => {
+                    for x in obj.iter()? {
+                        RVec::flatten(x?, parts)?;
+                    }
+                }
+            }
+        } else {
+            parts.push(RVec::operand(obj)?);
+        }
+        Ok(())
+    }
+
+    /// The elements and names at the positions in `index`
+    fn take(&self, index: RVecData) -> PyResult<RVec> {
+        let names = match &self.names {
+            Some(names) => Some(names.getindex(index.clone())?),
+            None => None,
+        };
+        Ok(RVec { data: self.data.getindex(index)?, names })
+    }
+
+    /// Wraps the result of an elementwise operation on self, keeping the names
+    fn with_data(&self, data: RVecData) -> RVec {
+        RVec { data, names: self.names.clone() }
+    }
+
+    /// Wraps the result of a binary operation, the names are taken from the first
+    /// operand that has names and the same length as the result, like R
+    fn binary(&self, other: &RVec, data: RVecData) -> RVec {
+        let names = [self, other].iter()
+            .filter_map(|x| x.names.as_ref())
+            .find(|names| names.len() == data.len())
+            .cloned();
+        RVec { data, names }
+    }
+
+    /// Converts a python index to RVecData, slices become the positions they select
+    /// and strings become the positions of the names. when assigning, all names must exist
+    fn index_data(&self, index: &PyAny, assign: bool) -> PyResult<RVecData> {
+        if let Ok(slice) = index.downcast::<PySlice>() {
+            let indices = slice.indices(self.data.len() as std::os::raw::c_long)?;
+            return Ok(slice_positions(indices.start, indices.step, indices.slicelength));
+        }
+        match RVec::operand(index)?.data {
+            RVecData::Str(keys) => {
+                let positions = name_positions(self.names.as_ref(), &keys);
+                if assign && keys.iter().zip(positions.iter()).any(|(k, p)| k.is_some() && p.is_none()) {
+                    return Err(RVecError::InvalidIndex("can't assign to names that are not in the vector".to_string()).into());
+                }
+                Ok(RVecData::Int(positions))
+            }
+            // an empty list selects nothing like R's x[integer(0)], instead of being a bool mask
+            RVecData::Bool(b) if b.is_empty() => Ok(RVecData::Int(vec![])),
+            index => Ok(index),
+        }
+    }
+}
+
+/// The positions that sort by the first vector, then by the next ones for ties, like R's order
+#[pyfunction]
+#[pyo3(signature = (*keys, decreasing = false, na_last = true))]
+fn order(keys: Vec<RVec>, decreasing: bool, na_last: Option<bool>) -> PyResult<RVec> {
+    let keys = keys.iter().map(|k| &k.data).collect::<Vec<_>>();
+    let positions = vec_sort::order_keys(&keys, decreasing, na_last)?;
+    Ok(RVec { data: RVecData::Int(positions.into_iter().map(|i| Some(i as Idef)).collect()), names: None })
+}
+
+/// Counts the values of `x` like RVec.table, or with `y` counts each pair of values and
+/// returns a dict from each value of `x` to the counts for the values of `y`
+#[pyfunction]
+#[pyo3(signature = (x, y = None, use_na = false))]
+fn table(py: Python, x: RVec, y: Option<RVec>, use_na: bool) -> PyResult<PyObject> {
+    let y = match y {
+        Some(y) => y,
+        None => return Ok(x.table(false, use_na)?.into_py(py)),
+    };
+    let (rows, cols, counts) = vec_table::crosstab(&x.data, &y.data, use_na)?;
+    let table = PyDict::new(py);
+    for (row, counts) in rows.to_list()?.into_iter().zip(counts) {
+        let counts = RVec { data: RVecData::Int(counts.into_iter().map(Some).collect()), names: Some(cols.as_str()) };
+        table.set_item(row, counts.into_py(py))?;
+    }
+    Ok(table.into())
+}
+
+/// A sequence of numbers like R's seq, ints if the arguments are ints. without `by` or
+/// `length_out` it steps by 1 or -1 from `from_` to `to`, or from 1 to `from_` if it is the only
+/// argument. from a date it is a sequence of
+/// dates, where `by` is a number of days or a step like "month" or "2 weeks"
+#[pyfunction]
+#[pyo3(signature = (from_ = None, to = None, by = None, length_out = None))]
+fn seq(from_: Option<&PyAny>, to: Option<&PyAny>, by: Option<&PyAny>, length_out: Option<usize>) -> PyResult<RVec> {
+    if let Some(from_) = from_.and_then(|x| x.extract::<NaiveDate>().ok()) {
+        let to = to.map(|x| x.extract::<NaiveDate>()).transpose()?.map(vec_date::from_date);
+        let (n, unit) = match by {
+            Some(by) => match by.extract::<i32>() {
+                Ok(n) => (n, vec_date::DateUnit::Day),
+                Err(_) => vec_date::parse_step(by.extract()?)?,
+            },
+            None => (1, vec_date::DateUnit::Day),
+        };
+        return Ok(RVec { data: vec_date::seq_dates(vec_date::from_date(from_), to, n, unit, length_out)?, names: None });
+    }
+    let args = [from_, to, by];
+    let ints = args.iter().flatten().all(|x| x.extract::<Idef>().is_ok());
+    let [from_, to, by] = args.map(|x| x.map(|x| x.extract::<f64>()).transpose());
+    Ok(RVec { data: vec_seq::seq(from_?, to?, by?, length_out, ints)?, names: None })
+}
+
+/// The ints from 1 to `length_out`, like R
+#[pyfunction]
+fn seq_len(length_out: usize) -> RVec {
+    RVec { data: vec_seq::seq_len(length_out), names: None }
+}
+
+/// The ints from 1 to the length of `x`, like R
+#[pyfunction]
+fn seq_along(x: &PyAny) -> PyResult<RVec> {
+    Ok(seq_len(RVec::operand(x)?.data.len()))
+}
+
+/// Repeats `x`, each element `each` times and then the whole `times` times. `times` can also give
+/// the count for each element, and `length_out` repeats until that length instead. names are kept
+#[pyfunction]
+#[pyo3(signature = (x, times = None, each = 1, length_out = None))]
+fn rep(x: &PyAny, times: Option<&PyAny>, each: usize, length_out: Option<usize>) -> PyResult<RVec> {
+    let x = RVec::operand(x)?;
+    let times = match times {
+        Some(times) => times.extract::<i64>().map(|t| vec![t]).or_else(|_| times.extract::<Vec<i64>>())?,
+        None => vec![1],
+    };
+    // negative counts are checked here, so they aren't reported as the wrong python type
+    if times.iter().any(|t| *t < 0) {
+        return Err(RVecError::InvalidArgument("invalid 'times' argument".to_string()).into());
+    }
+    let times = times.into_iter().map(|t| t as usize).collect::<Vec<_>>();
+    let positions = vec_seq::rep_positions(x.data.len(), &times, each, length_out)?;
+    x.take(RVecData::Int(positions.into_iter().map(|i| Some(i as Idef)).collect()))
+}
+
+/// Combines values into one RVec like R's c(). lists, tuples and RVecs are flattened and the
+/// result has the common type of all the values (see RType). names are kept, dict keys become
+/// names, and so do keyword arguments, numbered for vectors of more than one element like R
+#[pyfunction]
+#[pyo3(signature = (*args, **kwargs))]
+fn c(args: &PyTuple, kwargs: Option<&PyDict>) -> PyResult<RVec> {
+    let mut parts = vec![];
+    for arg in args {
+        RVec::flatten(arg, &mut parts)?;
+    }
+    for (key, value) in kwargs.into_iter().flatten() {
+        let key = key.str()?.to_str()?;
+        let mut inner = vec![];
+        RVec::flatten(value, &mut inner)?;
+        let mut part = RVec::concat(&inner);
+        let names = match &part.names {
+            Some(RVecData::Str(names)) => names.iter()
+                .map(|name| Some(match name.as_deref() {
+                    Some("") | None => key.to_string(),
+                    Some(name) => format!("{}.{}", key, name),
+                }))
+                .collect(),
+            _ if part.data.len() == 1 => vec![Some(key.to_string())],
+            _ => (1..=part.data.len()).map(|i| Some(format!("{}{}", key, i))).collect(),
+        };
+        part.names = Some(RVecData::Str(names));
+        parts.push(part);
+    }
+    Ok(RVec::concat(&parts))
+}
+
+/// Converts `x` to a factor like R. the levels are the sorted distinct values unless given,
+/// values that aren't levels become NA, and `labels` renames the levels. names are kept
+#[pyfunction]
+#[pyo3(signature = (x, levels = None, labels = None, ordered = false))]
+fn factor(x: &PyAny, levels: Option<&PyAny>, labels: Option<&PyAny>, ordered: bool) -> PyResult<RVec> {
+    let x = RVec::operand(x)?;
+    let levels = levels.map(from_py).transpose()?;
+    let labels = labels.map(from_py).transpose()?;
+    let factor = vec_factor::factor(&x.data, levels.as_ref(), labels.as_ref(), ordered)?;
+    Ok(x.with_data(RVecData::Factor(factor)))
+}
+
+/// Converts `x` to dates, strings are parsed with a strftime `format` (yyyy-mm-dd by default)
+/// and numbers are days since 1970-01-01. names are kept
+#[pyfunction]
+#[pyo3(signature = (x, format = None))]
+fn as_date(x: &PyAny, format: Option<&str>) -> PyResult<RVec> {
+    let x = RVec::operand(x)?;
+    let dates = match (&x.data, format) {
+        (RVecData::Str(_) | RVecData::Factor(_), Some(format)) => match x.data.as_str() {
+            RVecData::Str(a) => RVecData::Date(vec_date::parse_dates(&a, format)?),
+            _ => unreachable!("as_str always gives strings"),
+        },
+        (data, _) => data.as_date(),
+    };
+    Ok(x.with_data(dates))
+}
+
+/// Converts `x` to datetimes. strings are parsed with a strftime `format` or as ISO 8601, where
+/// times without an offset are in `offset` (UTC by default). numbers are seconds since
+/// 1970-01-01 00:00:00 UTC. names are kept
+#[pyfunction]
+#[pyo3(signature = (x, format = None, offset = None))]
+fn as_datetime(x: &PyAny, format: Option<&str>, offset: Option<&str>) -> PyResult<RVec> {
+    let x = RVec::operand(x)?;
+    let offset = offset.map(vec_datetime::parse_offset).transpose()?;
+    let times = match &x.data {
+        RVecData::Str(_) | RVecData::Factor(_) => match x.data.as_str() {
+            RVecData::Str(a) => RVecData::Datetime(vec_datetime::parse_datetimes(&a, format, offset)?),
+            _ => unreachable!("as_str always gives strings"),
+        },
+        data => match (data.as_datetime(), offset) {
+            (RVecData::Datetime(d), Some(offset)) => RVecData::Datetime(Datetime { offset, ..d }),
+            (times, _) => times,
+        },
+    };
+    Ok(x.with_data(times))
+}
+
+/// Converts `x` to durations in `units`, numbers are lengths in `units` (secs by default) and
+/// strings like "1.5 hours" get units like R's difftime unless given. names are kept
+#[pyfunction]
+#[pyo3(signature = (x, units = None))]
+fn as_duration(x: &PyAny, units: Option<&str>) -> PyResult<RVec> {
+    let x = RVec::operand(x)?;
+    let units = units.map(vec_duration::parse_duration_units).transpose()?;
+    let durations = match (&x.data, units) {
+        (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_), Some(units)) => match x.data.as_float() {
+            RVecData::Float(values) => RVecData::Duration(Duration { values, units }),
+            _ => unreachable!("as_float always gives floats"),
+        },
+        (data, units) => match (data.as_duration(), units) {
+            (RVecData::Duration(d), Some(units)) => RVecData::Duration(d.to_units(units)),
+            (durations, _) => durations,
+        },
+    };
+    Ok(x.with_data(durations))
+}
+
+/// The real parts of complex numbers, like R
+#[pyfunction]
+#[pyo3(name = "Re")]
+fn re(x: &PyAny) -> PyResult<RVec> {
+    let x = RVec::operand(x)?;
+    Ok(x.with_data(vec_complex::parts(&x.data, Part::Re)?))
+}
+
+/// The imaginary parts of complex numbers, like R
+#[pyfunction]
+#[pyo3(name = "Im")]
+fn im(x: &PyAny) -> PyResult<RVec> {
+    let x = RVec::operand(x)?;
+    Ok(x.with_data(vec_complex::parts(&x.data, Part::Im)?))
+}
+
+/// The modulus of complex numbers, like R
+#[pyfunction]
+#[pyo3(name = "Mod")]
+fn modulus(x: &PyAny) -> PyResult<RVec> {
+    let x = RVec::operand(x)?;
+    Ok(x.with_data(vec_complex::parts(&x.data, Part::Mod)?))
+}
+
+/// The argument of complex numbers in radians, in (-pi, pi], like R
+#[pyfunction]
+#[pyo3(name = "Arg")]
+fn arg(x: &PyAny) -> PyResult<RVec> {
+    let x = RVec::operand(x)?;
+    Ok(x.with_data(vec_complex::parts(&x.data, Part::Arg)?))
+}
+
+/// The complex conjugates, like R
+#[pyfunction]
+#[pyo3(name = "Conj")]
+fn conj(x: &PyAny) -> PyResult<RVec> {
+    let x = RVec::operand(x)?;
+    Ok(x.with_data(vec_complex::parts(&x.data, Part::Conj)?))
+}
+
+/// Sets how negative indices are read, "exclude" drops those elements like R (-1 drops the first)
+/// and "from_end" counts from the end like python (-1 is the last)
+#[pyfunction]
+fn set_negative_index(mode: &str) -> PyResult<()> {
+    match mode {
+        "exclude" => vec_index::set_negative_index(NegativeIndex::Exclude),
+        "from_end" => vec_index::set_negative_index(NegativeIndex::FromEnd),
+        _ => return Err(pyo3::exceptions::PyValueError::new_err(format!("unknown negative index mode {}", mode))),
+    }
+    Ok(())
+}
+
+/// Returns how negative indices are read, "exclude" or "from_end"
+#[pyfunction]
+fn get_negative_index() -> &'static str {
+    match vec_index::negative_index() {
+        NegativeIndex::Exclude => "exclude",
+        NegativeIndex::FromEnd => "from_end",
     }
 }
 
 #[pymodule]
 fn rvec(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<RVec>()?;
+    m.add_function(wrap_pyfunction!(set_negative_index, m)?)?;
+    m.add_function(wrap_pyfunction!(get_negative_index, m)?)?;
+    m.add_function(wrap_pyfunction!(order, m)?)?;
+    m.add_function(wrap_pyfunction!(table, m)?)?;
+    m.add_function(wrap_pyfunction!(seq, m)?)?;
+    m.add_function(wrap_pyfunction!(seq_len, m)?)?;
+    m.add_function(wrap_pyfunction!(seq_along, m)?)?;
+    m.add_function(wrap_pyfunction!(rep, m)?)?;
+    m.add_function(wrap_pyfunction!(c, m)?)?;
+    m.add_function(wrap_pyfunction!(factor, m)?)?;
+    m.add_function(wrap_pyfunction!(as_date, m)?)?;
+    m.add_function(wrap_pyfunction!(as_datetime, m)?)?;
+    m.add_function(wrap_pyfunction!(as_duration, m)?)?;
+    m.add_function(wrap_pyfunction!(re, m)?)?;
+    m.add_function(wrap_pyfunction!(im, m)?)?;
+    m.add_function(wrap_pyfunction!(modulus, m)?)?;
+    m.add_function(wrap_pyfunction!(arg, m)?)?;
+    m.add_function(wrap_pyfunction!(conj, m)?)?;
 
     Ok(())
 }
\ No newline at end of file
@@ -1 +1,178 @@
-// TODO: math methods
\ No newline at end of file
+use crate::{vec_data::{RVecData, BaseRVecData, map_na, to_f64, warn}, vec_error::{RVecError, RVecResult}, vec_complex::Complex, Fdef};
+
+/// Elementwise math functions on numeric vectors, ints and bools are promoted to floats
+/// like R's doubles. NA stays NA and NaN stays NaN. sqrt, exp and log also take complex
+/// numbers, where the square root and log of a negative number aren't NaN
+pub trait VecMathMethods: Sized {
+    fn sqrt(&self) -> RVecResult<Self>;
+    fn exp(&self) -> RVecResult<Self>;
+    fn log(&self, base: Option<f64>) -> RVecResult<Self>;
+    fn log1p(&self) -> RVecResult<Self>;
+    fn expm1(&self) -> RVecResult<Self>;
+    fn sin(&self) -> RVecResult<Self>;
+    fn cos(&self) -> RVecResult<Self>;
+    fn tan(&self) -> RVecResult<Self>;
+    fn asin(&self) -> RVecResult<Self>;
+    fn acos(&self) -> RVecResult<Self>;
+    fn atan(&self) -> RVecResult<Self>;
+    fn sinh(&self) -> RVecResult<Self>;
+    fn cosh(&self) -> RVecResult<Self>;
+    fn tanh(&self) -> RVecResult<Self>;
+    fn asinh(&self) -> RVecResult<Self>;
+    fn acosh(&self) -> RVecResult<Self>;
+    fn atanh(&self) -> RVecResult<Self>;
+    fn floor(&self) -> RVecResult<Self>;
+    fn ceiling(&self) -> RVecResult<Self>;
+    fn trunc(&self) -> RVecResult<Self>;
+    fn round(&self, digits: i32) -> RVecResult<Self>;
+    fn signif(&self, digits: i32) -> RVecResult<Self>;
+}
+
+/// Applies `f` to every element as a float, warning like R if it turns a number into NaN
+fn float_map(data: &RVecData, op: &'static str, f: impl Fn(f64) -> f64) -> RVecResult<RVecData> {
+    match data {
+        RVecData::Str(_) | RVecData::Complex(_) | RVecData::Factor(_) | RVecData::Date(_) | RVecData::Datetime(_) | RVecData::Duration(_) => Err(RVecError::UnsupportedType { op, dtype: data.element_type() }),
+        _ => match data.as_float() {
+            RVecData::Float(a) => {
+                let result = map_na(&a, |x| f(to_f64(*x)) as Fdef);
+                if result.iter().zip(a.iter()).any(|(r, x)| r.is_some_and(|r| r.is_nan()) && x.is_some_and(|x| !x.is_nan())) {
+                    warn("NaNs produced");
+                }
+                Ok(RVecData::Float(result))
+            }
+            _ => unreachable!("as_float always gives floats"),
+        },
+    }
+}
+
+/// Rounds `x` to `digits` decimal places with halves to even like R (IEC 60559), negative
+/// digits round to tens, hundreds, ... the scaling is a division for those, as 0.1 isn't exact.
+/// with decimal places it is R 4's algorithm, the closer of the two candidates to `x` is taken,
+/// so 0.15 rounds to 0.1 as it is just below 0.15 in binary, and only exact ties go to even
+fn round_half_even(x: f64, digits: i32) -> f64 {
+    let scale = 10f64.powi(digits.abs());
+    let scaled = if digits >= 0 { x * scale } else { x / scale };
+    if !scaled.is_finite() {
+        return x;
+    }
+    if digits <= 0 {
+        return scaled.round_ties_even() * scale;
+    }
+    let (down, up) = (scaled.floor(), scaled.ceil());
+    let (x_down, x_up) = (down / scale, up / scale);
+    let (d_down, d_up) = (x - x_down, x_up - x);
+    if d_up < d_down || (d_up == d_down && down % 2.0 != 0.0) { x_up } else { x_down }
+}
+
+/// Rounds `x` to `digits` significant digits, like R's signif
+fn signif(x: f64, digits: i32) -> f64 {
+    if x == 0.0 || !x.is_finite() {
+        return x;
+    }
+    let magnitude = x.abs().log10().floor() as i32 + 1;
+    round_half_even(x, digits.max(1) - magnitude)
+}
+
+impl VecMathMethods for RVecData {
+    fn sqrt(&self) -> RVecResult<Self> {
+        match self {
+            RVecData::Complex(a) => Ok(RVecData::Complex(map_na(a, Complex::sqrt))),
+            x => float_map(x, "sqrt()", f64::sqrt),
+        }
+    }
+
+    fn exp(&self) -> RVecResult<Self> {
+        match self {
+            RVecData::Complex(a) => Ok(RVecData::Complex(map_na(a, Complex::exp))),
+            x => float_map(x, "exp()", f64::exp),
+        }
+    }
+
+    // the natural log when there is no base
+    fn log(&self, base: Option<f64>) -> RVecResult<Self> {
+        match (self, base) {
+            (RVecData::Complex(a), Some(base)) => Ok(RVecData::Complex(map_na(a, |x| x.ln() / Complex::real(base as Fdef).ln()))),
+            (RVecData::Complex(a), None) => Ok(RVecData::Complex(map_na(a, Complex::ln))),
+            (x, Some(base)) => float_map(x, "log()", |x| x.log(base)),
+            (x, None) => float_map(x, "log()", f64::ln),
+        }
+    }
+
+    fn log1p(&self) -> RVecResult<Self> {
+        float_map(self, "log1p()", f64::ln_1p)
+    }
+
+    fn expm1(&self) -> RVecResult<Self> {
+        float_map(self, "expm1()", f64::exp_m1)
+    }
+
+    fn sin(&self) -> RVecResult<Self> {
+        float_map(self, "sin()", f64::sin)
+    }
+
+    fn cos(&self) -> RVecResult<Self> {
+        float_map(self, "cos()", f64::cos)
+    }
+
+    fn tan(&self) -> RVecResult<Self> {
+        float_map(self, "tan()", f64::tan)
+    }
+
+    fn asin(&self) -> RVecResult<Self> {
+        float_map(self, "asin()", f64::asin)
+    }
+
+    fn acos(&self) -> RVecResult<Self> {
+        float_map(self, "acos()", f64::acos)
+    }
+
+    fn atan(&self) -> RVecResult<Self> {
+        float_map(self, "atan()", f64::atan)
+    }
+
+    fn sinh(&self) -> RVecResult<Self> {
+        float_map(self, "sinh()", f64::sinh)
+    }
+
+    fn cosh(&self) -> RVecResult<Self> {
+        float_map(self, "cosh()", f64::cosh)
+    }
+
+    fn tanh(&self) -> RVecResult<Self> {
+        float_map(self, "tanh()", f64::tanh)
+    }
+
+    fn asinh(&self) -> RVecResult<Self> {
+        float_map(self, "asinh()", f64::asinh)
+    }
+
+    fn acosh(&self) -> RVecResult<Self> {
+        float_map(self, "acosh()", f64::acosh)
+    }
+
+    fn atanh(&self) -> RVecResult<Self> {
+        float_map(self, "atanh()", f64::atanh)
+    }
+
+    fn floor(&self) -> RVecResult<Self> {
+        float_map(self, "floor()", f64::floor)
+    }
+
+    fn ceiling(&self) -> RVecResult<Self> {
+        float_map(self, "ceiling()", f64::ceil)
+    }
+
+    fn trunc(&self) -> RVecResult<Self> {
+        float_map(self, "trunc()", f64::trunc)
+    }
+
+    // halves are rounded to even like R, so 0.5 and 2.5 round to 0 and 2
+    fn round(&self, digits: i32) -> RVecResult<Self> {
+        float_map(self, "round()", |x| round_half_even(x, digits))
+    }
+
+    // less than one digit is treated as one, like R
+    fn signif(&self, digits: i32) -> RVecResult<Self> {
+        float_map(self, "signif()", |x| signif(x, digits))
+    }
+}
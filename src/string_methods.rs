@@ -1,4 +1,4 @@
-use crate::{vec_data::RVecData, Idef};
+use crate::{vec_data::{RVecData, BaseRVecData, map_na}, vec_error::{RVecError, RVecResult}, Idef};
 
 /// A trait for PythonStr that implements string methods, on strings
 pub trait PyStringMethods {
@@ -7,12 +7,9 @@ pub trait PyStringMethods {
     fn count(&self, sub: &str) -> usize;
     fn endswith(&self, suffix: &str) -> bool;
     fn startswith(&self, prefix: &str) -> bool;
-    fn find(&self, sub: &str) -> Option<usize>;
     // fn join(&self, sep: &str) -> String;
     fn lower(&self) -> String;
     fn upper(&self) -> String;
-    fn replace(&self, old: &str, new: &str, count: Option<usize>) -> String;
-    fn split(&self, sep: &str) -> Vec<&str>;
     fn strip(&self) -> String;
     fn lstrip(&self) -> String;
     fn rstrip(&self) -> String;
@@ -49,10 +46,6 @@ impl PyStringMethods for str {
         self.starts_with(prefix)
     }
 
-    fn find(&self, sub: &str) -> Option<usize> {
-        self.find(sub)
-    }
-
     fn lower(&self) -> String {
         self.to_lowercase()
     }
@@ -61,14 +54,6 @@ impl PyStringMethods for str {
         self.to_uppercase()
     }
 
-    fn replace(&self, old: &str, new: &str, count: Option<usize>) -> String {
-        self.replacen(old, new, count.unwrap_or(usize::MAX))
-    }
-
-    fn split(&self, sep: &str) -> Vec<&str> {
-        self.split(sep).collect()
-    }
-
     fn strip(&self) -> String {
         self.trim().to_owned()
     }
@@ -82,112 +67,113 @@ impl PyStringMethods for str {
     }
 }
 
-pub trait VecStringMethods {
-    fn capitalize(&self) -> Self;
-    fn center(&self, width: usize, fill_char: char) -> Self;
-    fn count(&self, sub: &str) -> Self;
-    fn endswith(&self, suffix: &str) -> Self;
-    fn startswith(&self, prefix: &str) -> Self;
-    fn find(&self, sub: &str) -> Self;
-    // fn join(&self, iter: impl Iterator<Item = &str>) -> Self;
-    fn lower(&self) -> Self;
-    fn upper(&self) -> Self;
-    fn replace(&self, old: &str, new: &str) -> Self;
-    fn split(&self, sep: &str) -> Self;
-    fn strip(&self) -> Self;
-    fn lstrip(&self) -> Self;
-    fn rstrip(&self) -> Self;
+/// String methods on string vectors, NA stays NA
+pub trait VecStringMethods: Sized {
+    fn capitalize(&self) -> RVecResult<Self>;
+    fn center(&self, width: usize, fill_char: char) -> RVecResult<Self>;
+    fn count(&self, sub: &str) -> RVecResult<Self>;
+    fn endswith(&self, suffix: &str) -> RVecResult<Self>;
+    fn startswith(&self, prefix: &str) -> RVecResult<Self>;
+    fn find(&self, sub: &str) -> RVecResult<Self>;
+    // fn join(&self, iter: impl Iterator<Item = &str>) -> RVecResult<Self>;
+    fn lower(&self) -> RVecResult<Self>;
+    fn upper(&self) -> RVecResult<Self>;
+    fn replace(&self, old: &str, new: &str) -> RVecResult<Self>;
+    fn split(&self, sep: &str) -> RVecResult<Self>;
+    fn strip(&self) -> RVecResult<Self>;
+    fn lstrip(&self) -> RVecResult<Self>;
+    fn rstrip(&self) -> RVecResult<Self>;
 }
 
 impl VecStringMethods for RVecData {
-    fn capitalize(&self) -> Self {
+    fn capitalize(&self) -> RVecResult<Self> {
         match self {
-            RVecData::Str(s) => RVecData::Str(s.iter().map(|s| s.capitalize()).collect()),
-            _ => panic!("capitalize() called on non-string"),
+            RVecData::Str(s) => Ok(RVecData::Str(map_na(s, |s| s.capitalize()))),
+            a => Err(RVecError::UnsupportedType { op: "capitalize()", dtype: a.element_type() }),
         }
     }
 
-    fn center(&self, width: usize, fill_char: char) -> Self {
+    fn center(&self, width: usize, fill_char: char) -> RVecResult<Self> {
         match self {
-            RVecData::Str(s) => RVecData::Str(s.iter().map(|s| s.center(width, fill_char)).collect()),
-            _ => panic!("center() called on non-string"),
+            RVecData::Str(s) => Ok(RVecData::Str(map_na(s, |s| s.center(width, fill_char)))),
+            a => Err(RVecError::UnsupportedType { op: "center()", dtype: a.element_type() }),
         }
     }
 
-    fn count(&self, sub: &str) -> Self {
+    fn count(&self, sub: &str) -> RVecResult<Self> {
         match self {
-            RVecData::Str(s) => RVecData::Int(s.iter().map(|s| s.count(sub) as Idef).collect()),
-            _ => panic!("count() called on non-string"),
+            RVecData::Str(s) => Ok(RVecData::Int(map_na(s, |s| s.count(sub) as Idef))),
+            a => Err(RVecError::UnsupportedType { op: "count()", dtype: a.element_type() }),
         }
     }
 
-    fn endswith(&self, suffix: &str) -> Self {
+    fn endswith(&self, suffix: &str) -> RVecResult<Self> {
         match self {
-            RVecData::Str(s) => RVecData::Bool(s.iter().map(|s| s.endswith(suffix)).collect()),
-            _ => panic!("endswith() called on non-string"),
+            RVecData::Str(s) => Ok(RVecData::Bool(map_na(s, |s| s.endswith(suffix)))),
+            a => Err(RVecError::UnsupportedType { op: "endswith()", dtype: a.element_type() }),
         }
     }
 
-    fn startswith(&self, prefix: &str) -> Self {
+    fn startswith(&self, prefix: &str) -> RVecResult<Self> {
         match self {
-            RVecData::Str(s) => RVecData::Bool(s.iter().map(|s| s.startswith(prefix)).collect()),
-            _ => panic!("startswith() called on non-string"),
+            RVecData::Str(s) => Ok(RVecData::Bool(map_na(s, |s| s.startswith(prefix)))),
+            a => Err(RVecError::UnsupportedType { op: "startswith()", dtype: a.element_type() }),
         }
     }
 
-    fn find(&self, sub: &str) -> Self {
+    fn find(&self, sub: &str) -> RVecResult<Self> {
         match self {
-            RVecData::Str(s) => RVecData::Int(s.iter().map(|s| s.find(sub).unwrap_or(usize::MAX) as Idef).collect()),
-            _ => panic!("find() called on non-string"),
+            RVecData::Str(s) => Ok(RVecData::Int(map_na(s, |s| s.find(sub).map_or(-1, |i| i as Idef)))),
+            a => Err(RVecError::UnsupportedType { op: "find()", dtype: a.element_type() }),
         }
     }
 
-    fn lower(&self) -> Self {
+    fn lower(&self) -> RVecResult<Self> {
         match self {
-            RVecData::Str(s) => RVecData::Str(s.iter().map(|s| s.lower()).collect()),
-            _ => panic!("lower() called on non-string"),
+            RVecData::Str(s) => Ok(RVecData::Str(map_na(s, |s| s.lower()))),
+            a => Err(RVecError::UnsupportedType { op: "lower()", dtype: a.element_type() }),
         }
     }
 
-    fn upper(&self) -> Self {
+    fn upper(&self) -> RVecResult<Self> {
         match self {
-            RVecData::Str(s) => RVecData::Str(s.iter().map(|s| s.upper()).collect()),
-            _ => panic!("upper() called on non-string"),
+            RVecData::Str(s) => Ok(RVecData::Str(map_na(s, |s| s.upper()))),
+            a => Err(RVecError::UnsupportedType { op: "upper()", dtype: a.element_type() }),
         }
     }
 
-    fn replace(&self, old: &str, new: &str) -> Self {
+    fn replace(&self, old: &str, new: &str) -> RVecResult<Self> {
         match self {
-            RVecData::Str(s) => RVecData::Str(s.iter().map(|s| s.replace(old, new)).collect()),
-            _ => panic!("replace() called on non-string"),
+            RVecData::Str(s) => Ok(RVecData::Str(map_na(s, |s| s.replace(old, new)))),
+            a => Err(RVecError::UnsupportedType { op: "replace()", dtype: a.element_type() }),
         }
     }
 
-    fn split(&self, sep: &str) -> Self {
+    fn split(&self, sep: &str) -> RVecResult<Self> {
         match self {
-            RVecData::Str(s) => RVecData::Str(s.iter().map(|s| s.split(sep).collect()).collect()),
-            _ => panic!("split() called on non-string"),
+            RVecData::Str(s) => Ok(RVecData::Str(map_na(s, |s| s.split(sep).collect()))),
+            a => Err(RVecError::UnsupportedType { op: "split()", dtype: a.element_type() }),
         }
     }
 
-    fn strip(&self) -> Self {
+    fn strip(&self) -> RVecResult<Self> {
         match self {
-            RVecData::Str(s) => RVecData::Str(s.iter().map(|s| s.strip()).collect()),
-            _ => panic!("strip() called on non-string"),
+            RVecData::Str(s) => Ok(RVecData::Str(map_na(s, |s| s.strip()))),
+            a => Err(RVecError::UnsupportedType { op: "strip()", dtype: a.element_type() }),
         }
     }
 
-    fn lstrip(&self) -> Self {
+    fn lstrip(&self) -> RVecResult<Self> {
         match self {
-            RVecData::Str(s) => RVecData::Str(s.iter().map(|s| s.lstrip()).collect()),
-            _ => panic!("lstrip() called on non-string"),
+            RVecData::Str(s) => Ok(RVecData::Str(map_na(s, |s| s.lstrip()))),
+            a => Err(RVecError::UnsupportedType { op: "lstrip()", dtype: a.element_type() }),
         }
     }
 
-    fn rstrip(&self) -> Self {
+    fn rstrip(&self) -> RVecResult<Self> {
         match self {
-            RVecData::Str(s) => RVecData::Str(s.iter().map(|s| s.rstrip()).collect()),
-            _ => panic!("rstrip() called on non-string"),
+            RVecData::Str(s) => Ok(RVecData::Str(map_na(s, |s| s.rstrip()))),
+            a => Err(RVecError::UnsupportedType { op: "rstrip()", dtype: a.element_type() }),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,237 @@
+use std::cmp::Ordering;
+use crate::{vec_data::{RVecData, BaseRVecData, map_na, to_f64, warn}, vec_error::{RVecError, RVecResult}, vec_factor::Factor, vec_complex::Complex, vec_datetime::Datetime, vec_duration::Duration, vec_utils::concat, Fdef, Idef};
+
+/// Reductions of a vector to a summary, like R they give NA if there is an NA,
+/// unless `na_rm` is set, which removes NA and NaN first. bools count as 0 and 1.
+/// floats are summed in f64 with accurate_sum. complex numbers only have a sum, product and mean.
+pub trait VecAggregate: Sized {
+    fn sum(&self, na_rm: bool) -> RVecResult<Self>;
+    fn prod(&self, na_rm: bool) -> RVecResult<Self>;
+    fn mean(&self, na_rm: bool) -> RVecResult<Self>;
+    fn min(&self, na_rm: bool) -> RVecResult<Self>;
+    fn max(&self, na_rm: bool) -> RVecResult<Self>;
+    fn range(&self, na_rm: bool) -> RVecResult<Self>;
+    fn var(&self, na_rm: bool) -> RVecResult<Self>;
+    fn sd(&self, na_rm: bool) -> RVecResult<Self>;
+    fn median(&self, na_rm: bool) -> RVecResult<Self>;
+}
+
+/// The elements of `a`, without NA and NaN if `na_rm`. None if there is an NA that isn't removed
+fn present<T: Clone>(a: &[Option<T>], na_rm: bool, is_nan: impl Fn(&T) -> bool) -> Option<Vec<T>> {
+    if na_rm {
+        Some(a.iter().flatten().filter(|x| !is_nan(x)).cloned().collect())
+    } else {
+        a.iter().cloned().collect()
+    }
+}
+
+/// The numbers in `data` as f64, None if there is an NA that isn't removed
+fn numbers(data: &RVecData, op: &'static str, na_rm: bool) -> RVecResult<Option<Vec<f64>>> {
+    if let RVecData::Str(_) | RVecData::Complex(_) | RVecData::Factor(_) | RVecData::Date(_) | RVecData::Datetime(_) | RVecData::Duration(_) = data {
+        return Err(RVecError::UnsupportedType { op, dtype: data.element_type() });
+    }
+    match data.as_float() {
+        RVecData::Float(a) => Ok(present(&a, na_rm, |x| x.is_nan()).map(|a| a.into_iter().map(to_f64).collect())),
+        _ => unreachable!("as_float always gives floats"),
+    }
+}
+
+/// Applies `f` to the values of a duration and keeps its units like R's difftime,
+/// other vectors are passed to `f` as they are
+fn keep_units(data: &RVecData, f: impl Fn(&RVecData) -> RVecResult<RVecData>) -> RVecResult<RVecData> {
+    match data {
+        RVecData::Duration(d) => match f(&RVecData::Float(d.values.clone()))? {
+            RVecData::Float(values) => Ok(RVecData::Duration(Duration { values, units: d.units })),
+            x => Ok(x),
+        },
+        x => f(x),
+    }
+}
+
+/// The sum of complex numbers, the real and imaginary parts are summed with accurate_sum
+fn complex_sum(a: &[Complex]) -> Complex {
+    let re = a.iter().map(|x| to_f64(x.re)).collect::<Vec<_>>();
+    let im = a.iter().map(|x| to_f64(x.im)).collect::<Vec<_>>();
+    Complex::new(accurate_sum(&re) as Fdef, accurate_sum(&im) as Fdef)
+}
+
+/// A float vector with a single element
+fn float(x: Option<f64>) -> RVecData {
+    RVecData::Float(vec![x.map(|x| x as Fdef)])
+}
+
+/// A running sum with Neumaier's compensation, the low order bits lost when adding
+/// each value are kept in `c` and added back at the end
+#[derive(Default)]
+pub struct CompensatedSum {
+    sum: f64,
+    c: f64,
+}
+
+impl CompensatedSum {
+    pub fn add(&mut self, x: f64) {
+        let t = self.sum + x;
+        if self.sum.abs() >= x.abs() {
+            self.c += (self.sum - t) + x;
+        } else {
+            self.c += (x - t) + self.sum;
+        }
+        self.sum = t;
+    }
+
+    // the compensation is NaN once the sum overflows, so it is dropped
+    pub fn value(&self) -> f64 {
+        if self.sum.is_finite() { self.sum + self.c } else { self.sum }
+    }
+}
+
+/// Vectors up to this length are summed with CompensatedSum, longer ones are split in half
+const PAIRWISE_BLOCK: usize = 128;
+
+/// Sums `a` by recursively splitting it in halves, so the rounding error grows with log(n)
+/// instead of n and the result doesn't depend on how the vector is split up for parallel sums.
+/// the halves are merged with their compensations
+fn pairwise_sum(a: &[f64]) -> CompensatedSum {
+    if a.len() <= PAIRWISE_BLOCK {
+        let mut sum = CompensatedSum::default();
+        a.iter().for_each(|x| sum.add(*x));
+        sum
+    } else {
+        let (left, right) = a.split_at(a.len() / 2);
+        let (mut sum, right) = (pairwise_sum(left), pairwise_sum(right));
+        sum.add(right.sum);
+        sum.c += right.c;
+        sum
+    }
+}
+
+/// The sum of `a` with pairwise_sum
+fn accurate_sum(a: &[f64]) -> f64 {
+    pairwise_sum(a).value()
+}
+
+/// The mean and the sample variance of `a`. like R, the mean is corrected with the
+/// sum of the residuals, and the variance is the sum of squares around that mean
+fn mean_var(a: &[f64]) -> (f64, f64) {
+    let n = a.len() as f64;
+    let mean = accurate_sum(a) / n;
+    let residuals = a.iter().map(|x| x - mean).collect::<Vec<_>>();
+    let mean = if mean.is_finite() { mean + accurate_sum(&residuals) / n } else { mean };
+    let squares = a.iter().map(|x| (x - mean) * (x - mean)).collect::<Vec<_>>();
+    (mean, accurate_sum(&squares) / (n - 1.0))
+}
+
+/// The element of `a` that compares as `want` against all others, the first one on ties
+fn pick<T>(a: Vec<T>, cmp: impl Fn(&T, &T) -> Ordering, want: Ordering) -> Option<T> {
+    a.into_iter().reduce(|best, x| if cmp(&x, &best) == want { x } else { best })
+}
+
+/// The min or max of `data` in its own type, bools become ints. like R there is a warning and
+/// an infinite float when there are no values, and factors need to be ordered. dates and times keep their type
+fn extreme(data: &RVecData, na_rm: bool, want: Ordering) -> RVecResult<RVecData> {
+    let (op, inf) = if want == Ordering::Less { ("min()", f64::INFINITY) } else { ("max()", f64::NEG_INFINITY) };
+    match data {
+        RVecData::Factor(f) if !f.ordered => return Err(RVecError::UnsupportedType { op, dtype: "unordered factor" }),
+        // complex numbers have no order, like R
+        RVecData::Complex(_) => return Err(RVecError::UnsupportedType { op, dtype: "complex" }),
+        _ => (),
+    }
+    let empty = match data.is_na() {
+        RVecData::Bool(na) => na.iter().all(|x| *x == Some(true) && na_rm),
+        _ => unreachable!("is_na always gives bools"),
+    };
+    if empty {
+        warn(&format!("no non-missing arguments to {}; returning {}", op.trim_end_matches("()"), inf));
+        return Ok(float(Some(inf)));
+    }
+    Ok(match data {
+        RVecData::Float(a) => match present(a, na_rm, |x| x.is_nan()) {
+            Some(a) if a.iter().any(|x| x.is_nan()) => RVecData::Float(vec![Some(Fdef::NAN)]),
+            a => RVecData::Float(vec![a.and_then(|a| pick(a, |x, y| x.total_cmp(y), want))]),
+        },
+        RVecData::Str(a) => RVecData::Str(vec![present(a, na_rm, |_| false).and_then(|a| pick(a, |x, y| x.cmp(y), want))]),
+        RVecData::Factor(f) => {
+            let code = present(&f.codes, na_rm, |_| false).and_then(|a| pick(a, |x, y| x.cmp(y), want));
+            RVecData::Factor(Factor { codes: vec![code], ..f.clone() })
+        }
+        RVecData::Date(a) => RVecData::Date(vec![present(a, na_rm, |_| false).and_then(|a| pick(a, |x, y| x.cmp(y), want))]),
+        RVecData::Datetime(d) => {
+            let micros = present(&d.micros, na_rm, |_| false).and_then(|a| pick(a, |x, y| x.cmp(y), want));
+            RVecData::Datetime(Datetime { micros: vec![micros], offset: d.offset })
+        }
+        _ => match data.as_int() {
+            RVecData::Int(a) => RVecData::Int(vec![present(&a, na_rm, |_| false).and_then(|a| pick(a, |x, y| x.cmp(y), want))]),
+            _ => unreachable!("as_int always gives ints"),
+        },
+    })
+}
+
+impl VecAggregate for RVecData {
+    // sums of ints are ints, unless they don't fit in an int, then they are floats
+    fn sum(&self, na_rm: bool) -> RVecResult<Self> {
+        let a = match self {
+            d @ RVecData::Duration(_) => return keep_units(d, |x| x.sum(na_rm)),
+            RVecData::Complex(a) => return Ok(RVecData::Complex(vec![present(a, na_rm, Complex::is_nan).map(|a| complex_sum(&a))])),
+            RVecData::Int(a) => a.clone(),
+            RVecData::Bool(a) => map_na(a, |x| *x as Idef),
+            _ => return Ok(float(numbers(self, "sum()", na_rm)?.map(|a| accurate_sum(&a)))),
+        };
+        Ok(match present(&a, na_rm, |_| false) {
+            Some(a) => {
+                let total = a.iter().map(|x| *x as i128).sum::<i128>();
+                Idef::try_from(total).map_or_else(|_| float(Some(total as f64)), |x| RVecData::Int(vec![Some(x)]))
+            }
+            None => RVecData::Int(vec![None]),
+        })
+    }
+
+    // always a float, like R
+    fn prod(&self, na_rm: bool) -> RVecResult<Self> {
+        if let RVecData::Complex(a) = self {
+            return Ok(RVecData::Complex(vec![present(a, na_rm, Complex::is_nan).map(|a| a.into_iter().fold(Complex::real(1.0), |p, x| p * x))]));
+        }
+        Ok(float(numbers(self, "prod()", na_rm)?.map(|a| a.iter().product())))
+    }
+
+    // the mean of no values is NaN
+    fn mean(&self, na_rm: bool) -> RVecResult<Self> {
+        if let RVecData::Complex(a) = self {
+            return Ok(RVecData::Complex(vec![present(a, na_rm, Complex::is_nan).map(|a| complex_sum(&a) / Complex::real(a.len() as Fdef))]));
+        }
+        keep_units(self, |x| Ok(float(numbers(x, "mean()", na_rm)?.map(|a| mean_var(&a).0))))
+    }
+
+    fn min(&self, na_rm: bool) -> RVecResult<Self> {
+        keep_units(self, |x| extreme(x, na_rm, Ordering::Less))
+    }
+
+    fn max(&self, na_rm: bool) -> RVecResult<Self> {
+        keep_units(self, |x| extreme(x, na_rm, Ordering::Greater))
+    }
+
+    // the min and the max
+    fn range(&self, na_rm: bool) -> RVecResult<Self> {
+        Ok(concat(&[&self.min(na_rm)?, &self.max(na_rm)?]))
+    }
+
+    // the sample variance, NA for less than two values
+    fn var(&self, na_rm: bool) -> RVecResult<Self> {
+        Ok(float(numbers(self, "var()", na_rm)?.filter(|a| a.len() > 1).map(|a| mean_var(&a).1)))
+    }
+
+    fn sd(&self, na_rm: bool) -> RVecResult<Self> {
+        keep_units(self, |x| Ok(float(numbers(x, "sd()", na_rm)?.filter(|a| a.len() > 1).map(|a| mean_var(&a).1.sqrt()))))
+    }
+
+    // always a float, NA if there are no values or a NaN
+    fn median(&self, na_rm: bool) -> RVecResult<Self> {
+        keep_units(self, |x| {
+            let a = numbers(x, "median()", na_rm)?.filter(|a| !a.is_empty() && !a.iter().any(|x| x.is_nan()));
+            Ok(float(a.map(|mut a| {
+                a.sort_by(|x, y| x.total_cmp(y));
+                let mid = a.len() / 2;
+                if a.len() % 2 == 0 { (a[mid - 1] + a[mid]) / 2.0 } else { a[mid] }
+            })))
+        })
+    }
+}
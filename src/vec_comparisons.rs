@@ -1,229 +1,101 @@
-use crate::{Idef, Fdef, RVecData, vec_data::BaseRVecData};
+use std::{borrow::Cow, cmp::Ordering};
+use crate::{RVecData, vec_data::{RType, BaseRVecData, coerce_pair, warn, zip_with}, vec_error::{RVecError, RVecResult}, vec_factor::Factor, vec_duration::Duration};
 
 pub trait ElementCmp {
-    fn eq_ew(&self, other: &Self) -> RVecData;
-    fn ne_ew(&self, other: &Self) -> RVecData;
-    fn gt_ew(&self, other: &Self) -> RVecData;
-    fn ge_ew(&self, other: &Self) -> RVecData;
-    fn lt_ew(&self, other: &Self) -> RVecData;
-    fn le_ew(&self, other: &Self) -> RVecData;
+    fn eq_ew(&self, other: &Self) -> RVecResult<RVecData>;
+    fn ne_ew(&self, other: &Self) -> RVecResult<RVecData>;
+    fn gt_ew(&self, other: &Self) -> RVecResult<RVecData>;
+    fn ge_ew(&self, other: &Self) -> RVecResult<RVecData>;
+    fn lt_ew(&self, other: &Self) -> RVecResult<RVecData>;
+    fn le_ew(&self, other: &Self) -> RVecResult<RVecData>;
 }
 
-impl ElementCmp for RVecData {
-    fn eq_ew(&self, other: &Self) -> RVecData {
-        if self.len() == other.len() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x == y).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x == y).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| *x as Fdef == *y).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| *x == *y as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x == y).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x == y).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if self.is_scalar() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(b.iter().map(|x| a[0] == *x).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(b.iter().map(|x| a[0] == *x).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(b.iter().map(|x| a[0] as Fdef == *x).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(b.iter().map(|x| a[0] == *x as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(b.iter().map(|x| a[0] == *x).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(b.iter().map(|x| a[0] == *x).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if other.is_scalar() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(a.iter().map(|x| *x == b[0]).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(a.iter().map(|x| *x == b[0]).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(a.iter().map(|x| *x as Fdef == b[0]).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(a.iter().map(|x| *x == b[0] as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(a.iter().map(|x| *x == b[0]).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().map(|x| *x == b[0]).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else {
-            panic!("Cannot compare vectors of different lengths: {} and {}", self.len(), other.len())
+/// Compares `a` and `b` elementwise, true where `pred` holds for their ordering.
+/// NA or NaN in either operand gives NA, like R.
+fn cmp_na<A, B>(a: &[Option<A>], b: &[Option<B>], cmp: impl Fn(&A, &B) -> Option<Ordering>, pred: impl Fn(Ordering) -> bool) -> RVecResult<RVecData> {
+    Ok(RVecData::Bool(zip_with(a, b, |x, y| cmp(x?, y?).map(&pred))?))
+}
+
+/// Reads strings compared with an ordered factor as its levels, so they compare by level like R.
+/// strings compared with dates, datetimes or durations are read as them, so offsets and units are
+/// taken into account. numbers compared with a duration are in its units, like R
+fn read_strings<'a>(lhs: &'a RVecData, rhs: &'a RVecData) -> (Cow<'a, RVecData>, Cow<'a, RVecData>) {
+    let in_units = |n: &RVecData, d: &Duration| match n.as_float() {
+        RVecData::Float(values) => RVecData::Duration(Duration { values, units: d.units }),
+        _ => unreachable!("as_float always gives floats"),
+    };
+    match (lhs, rhs) {
+        (RVecData::Date(_) | RVecData::Datetime(_) | RVecData::Duration(_), s @ RVecData::Str(_)) => (Cow::Borrowed(lhs), Cow::Owned(s.coerce(lhs.rtype()))),
+        (s @ RVecData::Str(_), RVecData::Date(_) | RVecData::Datetime(_) | RVecData::Duration(_)) => (Cow::Owned(s.coerce(rhs.rtype())), Cow::Borrowed(rhs)),
+        (RVecData::Duration(d), n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_))) => (Cow::Borrowed(lhs), Cow::Owned(in_units(n, d))),
+        (n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_)), RVecData::Duration(d)) => (Cow::Owned(in_units(n, d)), Cow::Borrowed(rhs)),
+        (RVecData::Factor(f), RVecData::Str(s)) if f.ordered => {
+            (Cow::Borrowed(lhs), Cow::Owned(RVecData::Factor(Factor::from_labels(s, f.levels.clone(), true))))
         }
+        (RVecData::Str(s), RVecData::Factor(f)) if f.ordered => {
+            (Cow::Owned(RVecData::Factor(Factor::from_labels(s, f.levels.clone(), true))), Cow::Borrowed(rhs))
+        }
+        _ => (Cow::Borrowed(lhs), Cow::Borrowed(rhs)),
     }
+}
 
-    fn ne_ew(&self, other: &Self) -> RVecData {
-        if self.len() == other.len() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x != y).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x != y).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| *x as Fdef != *y).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| *x != *y as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x != y).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x != y).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if self.is_scalar() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(b.iter().map(|x| a[0] != *x).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(b.iter().map(|x| a[0] != *x).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(b.iter().map(|x| a[0] as Fdef != *x).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(b.iter().map(|x| a[0] != *x as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(b.iter().map(|x| a[0] != *x).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(b.iter().map(|x| a[0] != *x).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if other.is_scalar() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(a.iter().map(|x| *x != b[0]).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(a.iter().map(|x| *x != b[0]).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(a.iter().map(|x| *x as Fdef != b[0]).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(a.iter().map(|x| *x != b[0] as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(a.iter().map(|x| *x != b[0]).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().map(|x| *x != b[0]).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else {
-            panic!("Cannot compare vectors of different lengths: {} and {}", self.len(), other.len())
+/// Compares two vectors with `pred` after converting them to their common type (see RType),
+/// so 1 == 1.0 and 10 < "9" like in R. factors with the same levels compare by level. order
+/// is only meaningful for ordered factors, so other comparisons with an unordered factor give NA,
+/// whatever it is compared with.
+/// complex numbers have no order, so they can only be compared with == and !=
+fn compare(lhs: &RVecData, rhs: &RVecData, op: &'static str, pred: impl Fn(Ordering) -> bool) -> RVecResult<RVecData> {
+    let unordered = |x: &RVecData| matches!(x, RVecData::Factor(f) if !f.ordered);
+    if op != "==" && op != "!=" && (unordered(lhs) || unordered(rhs)) {
+        warn(&format!("{} is not meaningful for unordered factors", op));
+        let na = |x: &RVecData| match x.is_na() {
+            RVecData::Bool(a) => a,
+            _ => unreachable!("is_na always gives bools"),
+        };
+        return Ok(RVecData::Bool(zip_with(&na(lhs), &na(rhs), |_, _| None)?));
+    }
+    let (lhs, rhs) = read_strings(lhs, rhs);
+    let (a, b) = coerce_pair(&lhs, &rhs, RType::Bool);
+    match (a.as_ref(), b.as_ref()) {
+        (RVecData::Factor(a), RVecData::Factor(b)) => cmp_na(&a.codes, &b.codes, |x, y| x.partial_cmp(y), pred),
+        // unequal numbers are given any other ordering, it is only checked against Equal
+        (RVecData::Complex(a), RVecData::Complex(b)) if op == "==" || op == "!=" => {
+            cmp_na(a, b, |x, y| if x.is_nan() || y.is_nan() { None } else if x == y { Some(Ordering::Equal) } else { Some(Ordering::Less) }, pred)
         }
+        (RVecData::Complex(_), RVecData::Complex(_)) => Err(RVecError::UnsupportedType { op, dtype: "complex" }),
+        (RVecData::Int(a), RVecData::Int(b)) => cmp_na(a, b, |x, y| x.partial_cmp(y), pred),
+        (RVecData::Float(a), RVecData::Float(b)) => cmp_na(a, b, |x, y| x.partial_cmp(y), pred),
+        (RVecData::Str(a), RVecData::Str(b)) => cmp_na(a, b, |x, y| x.partial_cmp(y), pred),
+        (RVecData::Bool(a), RVecData::Bool(b)) => cmp_na(a, b, |x, y| x.partial_cmp(y), pred),
+        (RVecData::Date(a), RVecData::Date(b)) => cmp_na(a, b, |x, y| x.partial_cmp(y), pred),
+        (RVecData::Datetime(a), RVecData::Datetime(b)) => cmp_na(&a.micros, &b.micros, |x, y| x.partial_cmp(y), pred),
+        (RVecData::Duration(a), RVecData::Duration(b)) => cmp_na(&a.secs(), &b.secs(), |x, y| x.partial_cmp(y), pred),
+        _ => Err(RVecError::TypeMismatch { op, lhs: lhs.element_type(), rhs: rhs.element_type() }),
     }
+}
 
-    fn gt_ew(&self, other: &Self) -> RVecData {
-        if self.len() == other.len() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x > y).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x > y).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| *x as Fdef > *y).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| *x > *y as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x > y).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x > y).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if self.is_scalar() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(b.iter().map(|x| a[0] > *x).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(b.iter().map(|x| a[0] > *x).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(b.iter().map(|x| a[0] as Fdef > *x).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(b.iter().map(|x| a[0] > *x as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(b.iter().map(|x| a[0] > *x).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(b.iter().map(|x| a[0] > *x).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if other.is_scalar() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(a.iter().map(|x| *x > b[0]).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(a.iter().map(|x| *x > b[0]).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(a.iter().map(|x| *x as Fdef > b[0]).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(a.iter().map(|x| *x > b[0] as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(a.iter().map(|x| *x > b[0]).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().map(|x| *x > b[0]).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else {
-            panic!("Cannot compare vectors of different lengths: {} and {}", self.len(), other.len())
-        }
+impl ElementCmp for RVecData {
+    fn eq_ew(&self, other: &Self) -> RVecResult<RVecData> {
+        compare(self, other, "==", |o| o == Ordering::Equal)
     }
 
-    fn ge_ew(&self, other: &Self) -> RVecData {
-        if self.len() == other.len() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x >= y).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x >= y).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| *x as Fdef >= *y).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| *x >= *y as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x >= y).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x >= y).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if self.is_scalar() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(b.iter().map(|x| a[0] >= *x).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(b.iter().map(|x| a[0] >= *x).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(b.iter().map(|x| a[0] as Fdef >= *x).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(b.iter().map(|x| a[0] >= *x as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(b.iter().map(|x| a[0] >= *x).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(b.iter().map(|x| a[0] >= *x).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if other.is_scalar() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(a.iter().map(|x| *x >= b[0]).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(a.iter().map(|x| *x >= b[0]).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(a.iter().map(|x| *x as Fdef >= b[0]).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(a.iter().map(|x| *x >= b[0] as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(a.iter().map(|x| *x >= b[0]).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().map(|x| *x >= b[0]).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else {
-            panic!("Cannot compare vectors of different lengths: {} and {}", self.len(), other.len())
-        }
+    fn ne_ew(&self, other: &Self) -> RVecResult<RVecData> {
+        compare(self, other, "!=", |o| o != Ordering::Equal)
     }
 
-    fn lt_ew(&self, other: &Self) -> RVecData {
-        if self.len() == other.len() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x < y).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x < y).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| (*x as Fdef) < *y).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| *x < *y as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x < y).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x < y).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if self.is_scalar() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(b.iter().map(|x| a[0] < *x).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(b.iter().map(|x| a[0] < *x).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(b.iter().map(|x| (a[0] as Fdef) < *x).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(b.iter().map(|x| a[0] < *x as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(b.iter().map(|x| a[0] < *x).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(b.iter().map(|x| a[0] < *x).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if other.is_scalar() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(a.iter().map(|x| *x < b[0]).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(a.iter().map(|x| *x < b[0]).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(a.iter().map(|x| (*x as Fdef) < b[0]).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(a.iter().map(|x| *x < b[0] as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(a.iter().map(|x| *x < b[0]).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().map(|x| *x < b[0]).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else {
-            panic!("Cannot compare vectors of different lengths: {} and {}", self.len(), other.len())
-        }
+    fn gt_ew(&self, other: &Self) -> RVecResult<RVecData> {
+        compare(self, other, ">", |o| o == Ordering::Greater)
     }
 
-    fn le_ew(&self, other: &Self) -> RVecData {
-        if self.len() == other.len() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x <= y).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x <= y).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| (*x as Fdef) <= *y).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| *x <= *y as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x <= y).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().zip(b).map(|(x, y)| x <= y).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if self.is_scalar() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(b.iter().map(|x| a[0] <= *x).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(b.iter().map(|x| a[0] <= *x).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(b.iter().map(|x| a[0] as Fdef <= *x).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(b.iter().map(|x| a[0] <= *x as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(b.iter().map(|x| a[0] <= *x).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(b.iter().map(|x| a[0] <= *x).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if other.is_scalar() {
-            match (self, other) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Bool(a.iter().map(|x| *x <= b[0]).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Bool(a.iter().map(|x| *x <= b[0]).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Bool(a.iter().map(|x| *x as Fdef <= b[0]).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Bool(a.iter().map(|x| *x <= b[0] as Fdef).collect()),
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Bool(a.iter().map(|x| *x <= b[0]).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().map(|x| *x <= b[0]).collect()),
-                (a, b) => panic!("Cannot compare vectors of different types: {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else {
-            panic!("Cannot compare vectors of different lengths: {} and {}", self.len(), other.len())
-        }
+    fn ge_ew(&self, other: &Self) -> RVecResult<RVecData> {
+        compare(self, other, ">=", |o| o != Ordering::Less)
     }
-}
 
+    fn lt_ew(&self, other: &Self) -> RVecResult<RVecData> {
+        compare(self, other, "<", |o| o == Ordering::Less)
+    }
+
+    fn le_ew(&self, other: &Self) -> RVecResult<RVecData> {
+        compare(self, other, "<=", |o| o != Ordering::Greater)
+    }
+}
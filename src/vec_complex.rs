@@ -0,0 +1,209 @@
+use std::{fmt, ops::{Add, Sub, Mul, Div, Neg}};
+use pyo3::prelude::*;
+use pyo3::types::PyComplex;
+use crate::{vec_data::{RVecData, BaseRVecData, map_na, to_f64}, vec_error::{RVecError, RVecResult}, Fdef};
+
+/// A complex number like R's complex, NA is kept outside as None like the other types
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Complex {
+    pub re: Fdef,
+    pub im: Fdef,
+}
+
+impl Complex {
+    pub fn new(re: Fdef, im: Fdef) -> Complex {
+        Complex { re, im }
+    }
+
+    /// A complex number without an imaginary part
+    pub fn real(re: Fdef) -> Complex {
+        Complex { re, im: 0.0 }
+    }
+
+    /// NaN if either part is, like R
+    pub fn is_nan(&self) -> bool {
+        self.re.is_nan() || self.im.is_nan()
+    }
+
+    /// The modulus |z|, without overflowing for large parts
+    pub fn modulus(&self) -> Fdef {
+        self.re.hypot(self.im)
+    }
+
+    /// The argument in (-pi, pi]
+    pub fn arg(&self) -> Fdef {
+        self.im.atan2(self.re)
+    }
+
+    pub fn conj(&self) -> Complex {
+        Complex { re: self.re, im: -self.im }
+    }
+
+    pub fn exp(&self) -> Complex {
+        let r = self.re.exp();
+        Complex { re: r * self.im.cos(), im: r * self.im.sin() }
+    }
+
+    /// The principal natural log, the imaginary part is in (-pi, pi]
+    pub fn ln(&self) -> Complex {
+        Complex { re: self.modulus().ln(), im: self.arg() }
+    }
+
+    /// The principal square root, with a real part that isn't negative
+    pub fn sqrt(&self) -> Complex {
+        if self.re == 0.0 && self.im == 0.0 {
+            return Complex { re: 0.0, im: self.im };
+        }
+        let t = ((self.modulus() + self.re.abs()) / 2.0).sqrt();
+        if self.re >= 0.0 {
+            Complex { re: t, im: self.im / (2.0 * t) }
+        } else {
+            Complex { re: self.im.abs() / (2.0 * t), im: t.copysign(self.im) }
+        }
+    }
+
+    /// `self` to the power of `w` like R's cpow. whole powers are repeated multiplications,
+    /// so 1i ** 2 is exactly -1, and zero to a real power is a real power of zero
+    pub fn powc(&self, w: Complex) -> Complex {
+        if w.im == 0.0 && w.re.fract() == 0.0 && w.re.abs() <= 65536.0 {
+            let (mut k, mut base, mut result) = (w.re.abs() as u32, *self, Complex::real(1.0));
+            while k > 0 {
+                if k & 1 == 1 {
+                    result = result * base;
+                }
+                base = base * base;
+                k >>= 1;
+            }
+            return if w.re < 0.0 { Complex::real(1.0) / result } else { result };
+        }
+        if self.re == 0.0 && self.im == 0.0 {
+            return if w.im == 0.0 { Complex::real((0.0 as Fdef).powf(w.re)) } else { Complex::new(Fdef::NAN, Fdef::NAN) };
+        }
+        (w * self.ln()).exp()
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, rhs: Complex) -> Complex {
+        Complex { re: self.re + rhs.re, im: self.im + rhs.im }
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, rhs: Complex) -> Complex {
+        Complex { re: self.re - rhs.re, im: self.im - rhs.im }
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, rhs: Complex) -> Complex {
+        Complex { re: self.re * rhs.re - self.im * rhs.im, im: self.re * rhs.im + self.im * rhs.re }
+    }
+}
+
+// Smith's algorithm, which scales by the larger part of the divisor so it doesn't overflow
+impl Div for Complex {
+    type Output = Complex;
+    fn div(self, rhs: Complex) -> Complex {
+        let (a, b, c, d) = (self.re, self.im, rhs.re, rhs.im);
+        if c.abs() >= d.abs() {
+            let r = d / c;
+            let den = c + d * r;
+            Complex { re: (a + b * r) / den, im: (b - a * r) / den }
+        } else {
+            let r = c / d;
+            let den = c * r + d;
+            Complex { re: (a * r + b) / den, im: (b * r - a) / den }
+        }
+    }
+}
+
+impl Neg for Complex {
+    type Output = Complex;
+    fn neg(self) -> Complex {
+        Complex { re: -self.re, im: -self.im }
+    }
+}
+
+// like R, eg. 1+2i, 1-2i and 0+1i
+impl fmt::Display for Complex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.im < 0.0 { '-' } else { '+' };
+        write!(f, "{}{}{}i", self.re, sign, self.im.abs())
+    }
+}
+
+/// Parses complex numbers like R's as.complex, eg. "1+2i", "-2i" or "3"
+pub fn parse_complex(s: &str) -> Option<Complex> {
+    let Some(body) = s.strip_suffix('i') else {
+        return Some(Complex::real(s.parse().ok()?));
+    };
+    // the sign between the parts, which isn't the sign of an exponent
+    let split = body.char_indices().skip(1)
+        .filter(|(i, c)| (*c == '+' || *c == '-') && !body[..*i].ends_with(['e', 'E']))
+        .last().map(|(i, _)| i);
+    match split {
+        Some(i) => Some(Complex::new(body[..i].parse().ok()?, body[i..].parse().ok()?)),
+        None => Some(Complex::new(0.0, body.parse().ok()?)),
+    }
+}
+
+/// Reads a python list of complex numbers, which can have ints, floats and None in between.
+/// None if there isn't a python complex in it, those are read as other types
+pub fn complexes_from_py(obj: &PyAny) -> Option<Vec<Option<Complex>>> {
+    let items = obj.extract::<Vec<Option<&PyAny>>>().ok()?;
+    if !items.iter().flatten().any(|x| x.downcast::<PyComplex>().is_ok()) {
+        return None;
+    }
+    items.iter().map(|x| match x {
+        None => Some(None),
+        Some(x) => Some(Some(complex_from_py(x)?)),
+    }).collect()
+}
+
+/// A python complex, int or float as a complex number
+pub fn complex_from_py(obj: &PyAny) -> Option<Complex> {
+    match obj.downcast::<PyComplex>() {
+        Ok(z) => Some(Complex::new(z.real() as Fdef, z.imag() as Fdef)),
+        Err(_) => obj.extract::<Fdef>().ok().map(Complex::real),
+    }
+}
+
+pub fn complex_to_py(py: Python, z: &Complex) -> PyObject {
+    PyComplex::from_doubles(py, to_f64(z.re), to_f64(z.im)).to_object(py)
+}
+
+/// The parts of complex numbers, like R's functions of the same names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    Re,
+    Im,
+    /// the modulus
+    Mod,
+    /// the argument in radians
+    Arg,
+    /// the conjugate, which is complex
+    Conj,
+}
+
+/// A part of each complex number in `data`, ints, floats and bools are complex numbers
+/// without an imaginary part like R
+pub fn parts(data: &RVecData, part: Part) -> RVecResult<RVecData> {
+    let a = match data {
+        RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_) | RVecData::Complex(_) => match data.as_complex() {
+            RVecData::Complex(a) => a,
+            _ => unreachable!("as_complex always gives complex numbers"),
+        },
+        _ => return Err(RVecError::UnsupportedType { op: "complex parts", dtype: data.element_type() }),
+    };
+    Ok(match part {
+        Part::Re => RVecData::Float(map_na(&a, |x| x.re)),
+        Part::Im => RVecData::Float(map_na(&a, |x| x.im)),
+        Part::Mod => RVecData::Float(map_na(&a, Complex::modulus)),
+        Part::Arg => RVecData::Float(map_na(&a, Complex::arg)),
+        Part::Conj => RVecData::Complex(map_na(&a, Complex::conj)),
+    })
+}
@@ -0,0 +1,88 @@
+use crate::{vec_aggregate::CompensatedSum, vec_data::{RVecData, BaseRVecData, map_na, to_f64}, vec_error::{RVecError, RVecResult}, Fdef, Idef};
+
+/// Running totals of numeric vectors, NA from the first NA onward like R. bools count as 0 and 1
+pub trait VecCumulative: Sized {
+    fn cumsum(&self) -> RVecResult<Self>;
+    fn cumprod(&self) -> RVecResult<Self>;
+    fn cummax(&self) -> RVecResult<Self>;
+    fn cummin(&self) -> RVecResult<Self>;
+}
+
+/// Applies `f` to each element in order, every element from the first NA onward is NA
+fn accumulate<T: Copy, U>(a: &[Option<T>], mut f: impl FnMut(T) -> U) -> Vec<Option<U>> {
+    let mut na = false;
+    a.iter().map(|x| {
+        na = na || x.is_none();
+        if na { None } else { x.map(&mut f) }
+    }).collect()
+}
+
+/// The elements of an int or bool vector as ints, None for other types
+fn ints(data: &RVecData) -> Option<Vec<Option<Idef>>> {
+    match data {
+        RVecData::Int(a) => Some(a.clone()),
+        RVecData::Bool(a) => Some(map_na(a, |x| *x as Idef)),
+        _ => None,
+    }
+}
+
+/// The elements of a numeric vector as floats
+fn floats(data: &RVecData, op: &'static str) -> RVecResult<Vec<Option<Fdef>>> {
+    match data {
+        RVecData::Str(_) | RVecData::Complex(_) | RVecData::Factor(_) | RVecData::Date(_) | RVecData::Datetime(_) | RVecData::Duration(_) => Err(RVecError::UnsupportedType { op, dtype: data.element_type() }),
+        _ => match data.as_float() {
+            RVecData::Float(a) => Ok(a),
+            _ => unreachable!("as_float always gives floats"),
+        },
+    }
+}
+
+impl VecCumulative for RVecData {
+    // sums of ints are ints, unless one of them doesn't fit in an int, then they are all floats.
+    // float sums are compensated (see CompensatedSum)
+    fn cumsum(&self) -> RVecResult<Self> {
+        if let Some(a) = ints(self) {
+            let mut total = 0i128;
+            let sums = accumulate(&a, |x| { total += x as i128; total });
+            return if sums.iter().flatten().all(|x| Idef::try_from(*x).is_ok()) {
+                Ok(RVecData::Int(map_na(&sums, |x| *x as Idef)))
+            } else {
+                Ok(RVecData::Float(map_na(&sums, |x| *x as Fdef)))
+            };
+        }
+        let mut sum = CompensatedSum::default();
+        Ok(RVecData::Float(accumulate(&floats(self, "cumsum()")?, |x| { sum.add(to_f64(x)); sum.value() as Fdef })))
+    }
+
+    // always floats, like R
+    fn cumprod(&self) -> RVecResult<Self> {
+        let mut product = 1.0;
+        Ok(RVecData::Float(accumulate(&floats(self, "cumprod()")?, |x| { product *= to_f64(x); product as Fdef })))
+    }
+
+    // NaN stays NaN
+    fn cummax(&self) -> RVecResult<Self> {
+        if let Some(a) = ints(self) {
+            let mut best = Idef::MIN;
+            return Ok(RVecData::Int(accumulate(&a, |x| { best = best.max(x); best })));
+        }
+        let mut best = Fdef::NEG_INFINITY;
+        Ok(RVecData::Float(accumulate(&floats(self, "cummax()")?, |x| {
+            best = if best.is_nan() || x.is_nan() { Fdef::NAN } else { best.max(x) };
+            best
+        })))
+    }
+
+    // NaN stays NaN
+    fn cummin(&self) -> RVecResult<Self> {
+        if let Some(a) = ints(self) {
+            let mut best = Idef::MAX;
+            return Ok(RVecData::Int(accumulate(&a, |x| { best = best.min(x); best })));
+        }
+        let mut best = Fdef::INFINITY;
+        Ok(RVecData::Float(accumulate(&floats(self, "cummin()")?, |x| {
+            best = if best.is_nan() || x.is_nan() { Fdef::NAN } else { best.min(x) };
+            best
+        })))
+    }
+}
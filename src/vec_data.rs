@@ -1,29 +1,86 @@
+use std::{borrow::Cow, fmt};
 use pyo3::prelude::*;
-use crate::{Idef, Fdef};
+use pyo3::types::{PyComplex, PyDateTime, PyLong};
+use chrono::NaiveDate;
+use crate::{vec_datetime, Idef, Fdef, vec_error::{RVecError, RVecResult}, vec_factor::{Factor, factor}, vec_date::{ISO_DATE, to_date, from_date, date_to_py, days_to_int, int_to_days},
+    vec_datetime::{Datetime, TimeUnit, ISO_DATETIME, days_to_micros, micros_to_secs, secs_to_micros, datetimes_from_py, datetime_to_py},
+    vec_duration::{Duration, auto_units, unit_name, parse_durations, timedelta_secs, to_timedelta},
+    vec_complex::{Complex, parse_complex, complexes_from_py, complex_from_py, complex_to_py}};
 
-// A vector of data
+// A vector of data, `None` is a missing value (NA)
 #[derive(Debug, Clone)]
 pub enum RVecData {
-    Int(Vec<Idef>),
-    Float(Vec<Fdef>),
-    Str(Vec<String>),
-    Bool(Vec<bool>),
+    Int(Vec<Option<Idef>>),
+    Float(Vec<Option<Fdef>>),
+    Str(Vec<Option<String>>),
+    Bool(Vec<Option<bool>>),
+    Complex(Vec<Option<Complex>>),
+    Factor(Factor),
+    /// days since 1970-01-01, like R's Date
+    Date(Vec<Option<i32>>),
+    Datetime(Datetime),
+    Duration(Duration),
 }
 
+/// The types of RVecData in R's coercion order,
+/// bool < int < float < complex < duration < date < datetime < factor < str.
+/// Operations on two types convert both to the higher one, see `common_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RType {
+    Bool,
+    Int,
+    Float,
+    Complex,
+    Duration,
+    Date,
+    Datetime,
+    Factor,
+    Str,
+}
+
+/// The type both vectors are converted to for an operation, the higher of the two.
+/// factors only stay factors with other factors, with anything else they are their labels
+pub fn common_type(a: RType, b: RType) -> RType {
+    match a.max(b) {
+        RType::Factor if a != b => RType::Str,
+        to => to,
+    }
+}
+
+#[allow(dead_code)] // not every conversion is used by the bindings yet
 pub trait BaseRVecData {
     /// Returns the length of the vector
     fn len(&self) -> usize;
     /// Returns true if the vector is a scalar (length == 1)
     fn is_scalar(&self) -> bool;
-    /// Returns the type of the vector (int, float, str, bool)
+    /// Returns the type of the vector (int, float, str, bool, complex, factor, date, datetime, duration)
     fn element_type(&self) -> &'static str;
+    /// Returns the type of the vector for coercion
+    fn rtype(&self) -> RType;
+    /// Returns a bool vector that is true where the element is NA (or NaN)
+    fn is_na(&self) -> RVecData;
     /// Converts the vector to a vector of strings
     fn as_str(&self) -> RVecData;
-    /// Converts the vector to a vector of floats
+    /// Converts the vector to a vector of floats, strings that aren't numbers become NA.
+    /// factors give their codes from 1 like R, complex numbers lose their imaginary part
     fn as_float(&self) -> RVecData;
-    /// Converts the vector to a vector of ints
+    /// Converts the vector to a vector of ints, truncating floats. factors give their codes from 1 like R
     fn as_int(&self) -> RVecData;
-    /// Converts the vector to a vector of python objects
+    /// Converts the vector to a vector of bools, numbers are true if not zero
+    fn as_bool(&self) -> RVecData;
+    /// Converts the vector to complex numbers, strings are read like "1+2i"
+    fn as_complex(&self) -> RVecData;
+    /// Converts the vector to dates, numbers are days since 1970-01-01 and strings are read as
+    /// ISO dates (yyyy-mm-dd)
+    fn as_date(&self) -> RVecData;
+    /// Converts the vector to datetimes in UTC, numbers are seconds since 1970-01-01 00:00:00 UTC,
+    /// dates are at midnight UTC and strings are read as ISO 8601
+    fn as_datetime(&self) -> RVecData;
+    /// Converts the vector to durations, numbers are seconds and strings are read like "1.5 hours"
+    fn as_duration(&self) -> RVecData;
+    /// Converts the vector to the given type
+    fn coerce(&self, to: RType) -> RVecData;
+    /// Converts the vector to a vector of python objects, NA becomes None
     fn to_list(&self) -> PyResult<Vec<PyObject>>;
 }
 
@@ -34,6 +91,11 @@ impl BaseRVecData for RVecData {
             RVecData::Float(a) => a.len(),
             RVecData::Str(a) => a.len(),
             RVecData::Bool(a) => a.len(),
+            RVecData::Complex(a) => a.len(),
+            RVecData::Factor(f) => f.codes.len(),
+            RVecData::Date(a) => a.len(),
+            RVecData::Datetime(d) => d.micros.len(),
+            RVecData::Duration(d) => d.values.len(),
         }
     }
 
@@ -47,32 +109,177 @@ impl BaseRVecData for RVecData {
             RVecData::Float(_) => "float",
             RVecData::Str(_) => "str",
             RVecData::Bool(_) => "bool",
+            RVecData::Complex(_) => "complex",
+            RVecData::Factor(_) => "factor",
+            RVecData::Date(_) => "date",
+            RVecData::Datetime(_) => "datetime",
+            RVecData::Duration(_) => "duration",
+        }
+    }
+
+    fn rtype(&self) -> RType {
+        match self {
+            RVecData::Int(_) => RType::Int,
+            RVecData::Float(_) => RType::Float,
+            RVecData::Str(_) => RType::Str,
+            RVecData::Bool(_) => RType::Bool,
+            RVecData::Complex(_) => RType::Complex,
+            RVecData::Factor(_) => RType::Factor,
+            RVecData::Date(_) => RType::Date,
+            RVecData::Datetime(_) => RType::Datetime,
+            RVecData::Duration(_) => RType::Duration,
+        }
+    }
+
+    fn is_na(&self) -> RVecData {
+        match self {
+            RVecData::Int(a) => RVecData::Bool(a.iter().map(|x| Some(x.is_none())).collect()),
+            RVecData::Float(a) => RVecData::Bool(a.iter().map(|x| Some(x.is_none_or(|x| x.is_nan()))).collect()),
+            RVecData::Str(a) => RVecData::Bool(a.iter().map(|x| Some(x.is_none())).collect()),
+            RVecData::Bool(a) => RVecData::Bool(a.iter().map(|x| Some(x.is_none())).collect()),
+            RVecData::Complex(a) => RVecData::Bool(a.iter().map(|x| Some(x.is_none_or(|x| x.is_nan()))).collect()),
+            RVecData::Factor(f) => RVecData::Bool(f.codes.iter().map(|x| Some(x.is_none())).collect()),
+            RVecData::Date(a) => RVecData::Bool(a.iter().map(|x| Some(x.is_none())).collect()),
+            RVecData::Datetime(d) => RVecData::Bool(d.micros.iter().map(|x| Some(x.is_none())).collect()),
+            RVecData::Duration(d) => RVecData::Bool(d.values.iter().map(|x| Some(x.is_none_or(|x| x.is_nan()))).collect()),
         }
     }
 
     fn as_str(&self) -> RVecData {
         match self {
-            RVecData::Int(a) => RVecData::Str(a.iter().map(|x| x.to_string()).collect()),
-            RVecData::Float(a) => RVecData::Str(a.iter().map(|x| x.to_string()).collect()),
+            RVecData::Int(a) => RVecData::Str(map_na(a, |x| x.to_string())),
+            RVecData::Float(a) => RVecData::Str(map_na(a, |x| x.to_string())),
             RVecData::Str(a) => RVecData::Str(a.clone()),
-            RVecData::Bool(a) => RVecData::Str(a.iter().map(|x| x.to_string()).collect()),
+            RVecData::Bool(a) => RVecData::Str(map_na(a, |x| if *x { "TRUE" } else { "FALSE" }.to_string())),
+            RVecData::Complex(a) => RVecData::Str(map_na(a, |x| x.to_string())),
+            RVecData::Factor(f) => RVecData::Str(f.labels()),
+            RVecData::Date(a) => RVecData::Str(map_na_checked(a, |x| to_date(*x).map(|d| d.to_string()))),
+            RVecData::Datetime(d) => RVecData::Str(d.format(ISO_DATETIME).expect("the ISO format is valid")),
+            RVecData::Duration(d) => RVecData::Str(map_na(&d.values, |x| format!("{} {}", x, unit_name(d.units)))),
         }
     }
 
     fn as_float(&self) -> RVecData {
         match self {
-            RVecData::Int(a) => RVecData::Float(a.iter().map(|x| *x as Fdef).collect()),
+            RVecData::Int(a) => RVecData::Float(map_na(a, |x| *x as Fdef)),
             RVecData::Float(a) => RVecData::Float(a.clone()),
-            _ => panic!("Cannot convert to float"),
+            RVecData::Str(a) => RVecData::Float(parse_na(a, |x| x.parse().ok())),
+            RVecData::Bool(a) => RVecData::Float(map_na(a, |x| *x as Idef as Fdef)),
+            RVecData::Complex(a) => {
+                if a.iter().flatten().any(|x| x.im != 0.0 && !x.im.is_nan()) {
+                    warn("imaginary parts discarded in coercion");
+                }
+                RVecData::Float(map_na(a, |x| x.re))
+            }
+            RVecData::Factor(f) => RVecData::Float(map_na(&f.codes, |x| (*x + 1) as Fdef)),
+            RVecData::Date(a) => RVecData::Float(map_na(a, |x| *x as Fdef)),
+            RVecData::Datetime(d) => RVecData::Float(map_na(&d.micros, |x| (*x as f64 / 1e6) as Fdef)),
+            RVecData::Duration(d) => RVecData::Float(d.values.clone()),
         }
     }
 
     fn as_int(&self) -> RVecData {
         match self {
             RVecData::Int(a) => RVecData::Int(a.clone()),
-            RVecData::Float(a) => RVecData::Int(a.iter().map(|x| *x as Idef).collect()),
-            RVecData::Bool(a) => RVecData::Int(a.iter().map(|x| *x as Idef).collect()),
-            _ => panic!("Cannot convert to int"),
+            RVecData::Float(a) => RVecData::Int(map_na_checked(a, float_to_int)),
+            RVecData::Str(a) => RVecData::Int(parse_na(a, |x| x.parse().ok().or_else(|| float_to_int(&x.parse().ok()?)))),
+            RVecData::Bool(a) => RVecData::Int(map_na(a, |x| *x as Idef)),
+            c @ RVecData::Complex(_) => c.as_float().as_int(),
+            RVecData::Factor(f) => RVecData::Int(map_na(&f.codes, |x| (*x + 1) as Idef)),
+            RVecData::Date(a) => RVecData::Int(map_na(a, |x| days_to_int(*x))),
+            RVecData::Datetime(d) => RVecData::Int(map_na_checked(&d.micros, |x| micros_to_secs(*x))),
+            RVecData::Duration(d) => RVecData::Float(d.values.clone()).as_int(),
+        }
+    }
+
+    fn as_bool(&self) -> RVecData {
+        match self {
+            RVecData::Int(a) => RVecData::Bool(map_na(a, |x| *x != 0)),
+            RVecData::Float(a) => RVecData::Bool(map_na_checked(a, |x| if x.is_nan() { None } else { Some(*x != 0.0) })),
+            RVecData::Str(a) => RVecData::Bool(parse_na(a, |x| match x {
+                "TRUE" | "True" | "true" | "T" => Some(true),
+                "FALSE" | "False" | "false" | "F" => Some(false),
+                _ => None,
+            })),
+            RVecData::Bool(a) => RVecData::Bool(a.clone()),
+            // true if either part isn't zero, like R
+            RVecData::Complex(a) => RVecData::Bool(map_na_checked(a, |x| if x.is_nan() { None } else { Some(x.re != 0.0 || x.im != 0.0) })),
+            // like R, the labels are read as bools
+            f @ RVecData::Factor(_) => f.as_str().as_bool(),
+            d @ (RVecData::Date(_) | RVecData::Datetime(_)) => d.as_int().as_bool(),
+            RVecData::Duration(d) => RVecData::Float(d.values.clone()).as_bool(),
+        }
+    }
+
+    fn as_complex(&self) -> RVecData {
+        match self {
+            RVecData::Complex(a) => RVecData::Complex(a.clone()),
+            RVecData::Str(a) => RVecData::Complex(parse_na(a, parse_complex)),
+            x => match x.as_float() {
+                RVecData::Float(a) => RVecData::Complex(map_na(&a, |x| Complex::real(*x))),
+                _ => unreachable!("as_float always gives floats"),
+            },
+        }
+    }
+
+    fn as_date(&self) -> RVecData {
+        match self {
+            RVecData::Date(a) => RVecData::Date(a.clone()),
+            RVecData::Str(a) => RVecData::Date(parse_na(a, |x| NaiveDate::parse_from_str(x, ISO_DATE).ok().map(from_date))),
+            RVecData::Float(a) => RVecData::Date(map_na_checked(a, |x| int_to_days(float_to_int(x)?))),
+            // like R, the labels are read as dates
+            f @ RVecData::Factor(_) => f.as_str().as_date(),
+            // the date in the vector's offset
+            RVecData::Datetime(d) => RVecData::Date(d.local().iter().map(|t| t.map(|t| from_date(t.date()))).collect()),
+            x => match x.as_int() {
+                RVecData::Int(a) => RVecData::Date(map_na_checked(&a, |x| int_to_days(*x))),
+                _ => unreachable!("as_int always gives ints"),
+            },
+        }
+    }
+
+    fn as_datetime(&self) -> RVecData {
+        match self {
+            RVecData::Datetime(d) => RVecData::Datetime(d.clone()),
+            RVecData::Date(a) => RVecData::Datetime(Datetime::utc(map_na_checked(a, |x| days_to_micros(*x)))),
+            RVecData::Str(a) => RVecData::Datetime(vec_datetime::parse_datetimes(a, None, None).expect("parsing without a format can't fail")),
+            f @ RVecData::Factor(_) => f.as_str().as_datetime(),
+            x => match x.as_float() {
+                RVecData::Float(a) => RVecData::Datetime(Datetime::utc(map_na_checked(&a, |x| secs_to_micros(to_f64(*x))))),
+                _ => unreachable!("as_float always gives floats"),
+            },
+        }
+    }
+
+    fn as_duration(&self) -> RVecData {
+        match self {
+            RVecData::Duration(d) => RVecData::Duration(d.clone()),
+            RVecData::Str(a) => {
+                let secs = parse_durations(a);
+                RVecData::Duration(Duration::from_secs(&secs, auto_units(&secs)))
+            }
+            f @ RVecData::Factor(_) => f.as_str().as_duration(),
+            x => match x.as_float() {
+                RVecData::Float(a) => RVecData::Duration(Duration { values: a, units: TimeUnit::Sec }),
+                _ => unreachable!("as_float always gives floats"),
+            },
+        }
+    }
+
+    fn coerce(&self, to: RType) -> RVecData {
+        match to {
+            RType::Bool => self.as_bool(),
+            RType::Int => self.as_int(),
+            RType::Float => self.as_float(),
+            RType::Complex => self.as_complex(),
+            RType::Str => self.as_str(),
+            RType::Date => self.as_date(),
+            RType::Datetime => self.as_datetime(),
+            RType::Duration => self.as_duration(),
+            RType::Factor => match self {
+                RVecData::Factor(f) => RVecData::Factor(f.clone()),
+                x => RVecData::Factor(factor(x, None, None, false).expect("factor without levels or labels can't fail")),
+            },
         }
     }
 
@@ -83,30 +290,184 @@ impl BaseRVecData for RVecData {
                 RVecData::Float(a) => Ok(a.iter().map(|x| x.to_object(py)).collect()),
                 RVecData::Str(a) => Ok(a.iter().map(|x| x.to_object(py)).collect()),
                 RVecData::Bool(a) => Ok(a.iter().map(|x| x.to_object(py)).collect()),
+                RVecData::Complex(a) => Ok(a.iter().map(|x| x.as_ref().map_or_else(|| py.None(), |x| complex_to_py(py, x))).collect()),
+                RVecData::Factor(f) => Ok(f.labels().iter().map(|x| x.to_object(py)).collect()),
+                RVecData::Date(a) => a.iter().map(|x| match x {
+                    Some(x) => date_to_py(py, *x),
+                    None => Ok(py.None()),
+                }).collect(),
+                RVecData::Datetime(d) => d.micros.iter().map(|x| match x {
+                    Some(x) => datetime_to_py(py, *x, d.offset),
+                    None => Ok(py.None()),
+                }).collect(),
+                RVecData::Duration(d) => Ok(d.secs().iter().map(|x| x.and_then(to_timedelta).to_object(py)).collect()),
             }
         })
     }
 }
 
-/// Converts a Python object to RVecData
+/// Formats a single element, NA is written as `NA`
+fn fmt_na<T: fmt::Display>(x: &Option<T>) -> String {
+    match x {
+        Some(x) => x.to_string(),
+        None => "NA".to_string(),
+    }
+}
+
+/// Formats each element like they are printed, with R's NA, TRUE and FALSE and quoted strings.
+/// factor labels, dates, datetimes and durations aren't quoted, like R
+pub fn element_strings(data: &RVecData) -> Vec<String> {
+    match data {
+        RVecData::Int(a) => a.iter().map(fmt_na).collect(),
+        RVecData::Float(a) => a.iter().map(fmt_na).collect(),
+        RVecData::Str(a) => a.iter().map(|x| fmt_na(&x.as_ref().map(|x| format!("{:?}", x)))).collect(),
+        RVecData::Bool(a) => a.iter().map(|x| fmt_na(&x.map(|x| if x { "TRUE" } else { "FALSE" }))).collect(),
+        RVecData::Complex(a) => a.iter().map(fmt_na).collect(),
+        RVecData::Factor(f) => f.labels().iter().map(fmt_na).collect(),
+        RVecData::Date(a) => a.iter().map(|x| fmt_na(&x.and_then(to_date))).collect(),
+        RVecData::Datetime(d) => d.format(ISO_DATETIME).expect("the ISO format is valid").iter().map(fmt_na).collect(),
+        RVecData::Duration(d) => d.values.iter().map(|x| fmt_na(&x.map(|x| format!("{} {}", x, unit_name(d.units))))).collect(),
+    }
+}
+
+// prints like a python list, but with R's NA, TRUE and FALSE
+impl fmt::Display for RVecData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}]", element_strings(self).join(", "))
+    }
+}
+
+/// Applies `f` to every element, NA stays NA
+pub fn map_na<A, T>(a: &[Option<A>], f: impl Fn(&A) -> T) -> Vec<Option<T>> {
+    a.iter().map(|x| x.as_ref().map(&f)).collect()
+}
+
+/// Like `map_na`, but `f` can also return NA
+pub fn map_na_checked<A, T>(a: &[Option<A>], f: impl Fn(&A) -> Option<T>) -> Vec<Option<T>> {
+    a.iter().map(|x| x.as_ref().and_then(&f)).collect()
+}
+
+/// Parses every string with `f`, warning like R if any of them can't be parsed
+fn parse_na<T>(a: &[Option<String>], f: impl Fn(&str) -> Option<T>) -> Vec<Option<T>> {
+    let parsed = map_na_checked(a, |x| f(x.trim()));
+    if parsed.iter().zip(a.iter()).any(|(p, x)| p.is_none() && x.is_some()) {
+        warn("NAs introduced by coercion");
+    }
+    parsed
+}
+
+/// Widens a float to f64 for math and accumulators, this only does something with precision32
+#[allow(clippy::useless_conversion)]
+pub fn to_f64(x: Fdef) -> f64 {
+    f64::from(x)
+}
+
+/// Truncates a float to an int, NA if it is NaN or out of range
+fn float_to_int(x: &Fdef) -> Option<Idef> {
+    let x = x.trunc();
+    if x.is_nan() || x < Idef::MIN as Fdef || x > Idef::MAX as Fdef {
+        None
+    } else {
+        Some(x as Idef)
+    }
+}
+
+/// Converts both vectors to their common type, and at least to `min`.
+/// This is the one place where the promotion rules are applied, a vector that
+/// already has the right type is borrowed instead of copied.
+pub fn coerce_pair<'a>(lhs: &'a RVecData, rhs: &'a RVecData, min: RType) -> (Cow<'a, RVecData>, Cow<'a, RVecData>) {
+    let to = match (lhs, rhs) {
+        // factors with different levels are compared and combined by their labels
+        (RVecData::Factor(a), RVecData::Factor(b)) if a.levels != b.levels => RType::Str,
+        _ => common_type(lhs.rtype(), rhs.rtype()).max(min),
+    };
+    let convert = |x: &'a RVecData| if x.rtype() == to { Cow::Borrowed(x) } else { Cow::Owned(x.coerce(to)) };
+    (convert(lhs), convert(rhs))
+}
+
+/// Emits a python warning, used for things R would warn about
+pub fn warn(msg: &str) {
+    Python::with_gil(|py| {
+        // the warning is only an error if python is set to raise warnings, which we ignore
+        let _ = PyErr::warn(py, py.get_type::<pyo3::exceptions::PyUserWarning>(), msg, 1);
+    })
+}
+
+/// Applies `f` elementwise to `a` and `b` with full control over NA.
+/// The shorter operand is recycled to the length of the longer one like in R,
+/// with a warning if the longer length is not a multiple of the shorter.
+pub fn zip_with<A, B, T>(a: &[Option<A>], b: &[Option<B>], f: impl Fn(Option<&A>, Option<&B>) -> Option<T>) -> RVecResult<Vec<Option<T>>> {
+    let (n, m) = (a.len(), b.len());
+    if n == m {
+        Ok(a.iter().zip(b.iter()).map(|(x, y)| f(x.as_ref(), y.as_ref())).collect())
+    } else if n == 0 || m == 0 {
+        Ok(Vec::new())
+    } else {
+        let len = n.max(m);
+        if len % n.min(m) != 0 {
+            warn("longer object length is not a multiple of shorter object length");
+        }
+        Ok((0..len).map(|i| f(a[i % n].as_ref(), b[i % m].as_ref())).collect())
+    }
+}
+
+/// Applies `f` elementwise to `a` and `b`, the result is NA where either is NA
+pub fn zip_na<A, B, T>(a: &[Option<A>], b: &[Option<B>], f: impl Fn(&A, &B) -> T) -> RVecResult<Vec<Option<T>>> {
+    zip_with(a, b, |x, y| Some(f(x?, y?)))
+}
+
+/// Like `zip_na`, but `f` can also return NA, eg. on integer overflow
+pub fn zip_na_checked<A, B, T>(a: &[Option<A>], b: &[Option<B>], f: impl Fn(&A, &B) -> Option<T>) -> RVecResult<Vec<Option<T>>> {
+    zip_with(a, b, |x, y| f(x?, y?))
+}
+
+/// Converts a Python object to RVecData, None becomes NA
 pub fn from_py(obj: &PyAny) -> PyResult<RVecData> {
-    if let Ok(_) = obj.extract::<Vec<bool>>() {
+    if obj.extract::<Vec<Option<bool>>>().is_ok() {
         Ok(RVecData::Bool(obj.extract()?))
-    } else if let Ok(_) = obj.extract::<Vec<Idef>>() {
+    } else if obj.extract::<Vec<Option<Idef>>>().is_ok() {
         Ok(RVecData::Int(obj.extract()?))
-    } else if let Ok(_) = obj.extract::<Vec<Fdef>>() {
+    } else if obj.extract::<Vec<Option<&PyLong>>>().is_ok() {
+        // ints that don't fit are stored as floats instead of being truncated
+        warn("integers too large for int storage were converted to float");
         Ok(RVecData::Float(obj.extract()?))
-    } else if let Ok(_) = obj.extract::<Vec<String>>() {
+    } else if obj.extract::<Vec<Option<Fdef>>>().is_ok() {
+        Ok(RVecData::Float(obj.extract()?))
+    } else if obj.extract::<Vec<Option<String>>>().is_ok() {
         Ok(RVecData::Str(obj.extract()?))
-    } else if let Ok(_) = obj.extract::<bool>() {
+    } else if let Some(a) = complexes_from_py(obj) {
+        Ok(RVecData::Complex(a))
+    } else if let Ok(a) = obj.extract::<Vec<Option<chrono::Duration>>>() {
+        let secs = map_na(&a, |x| timedelta_secs(*x));
+        Ok(RVecData::Duration(Duration::from_secs(&secs, auto_units(&secs))))
+    } else if obj.extract::<Vec<Option<&PyDateTime>>>().is_ok() {
+        // datetimes are also dates in python, so they are checked first
+        Ok(RVecData::Datetime(datetimes_from_py(&obj.extract::<Vec<Option<&PyAny>>>()?)?))
+    } else if obj.extract::<Vec<Option<NaiveDate>>>().is_ok() {
+        Ok(RVecData::Date(map_na(&obj.extract::<Vec<Option<NaiveDate>>>()?, |d| from_date(*d))))
+    } else if obj.is_none() {
+        Ok(RVecData::Bool(vec![None]))
+    } else if obj.extract::<bool>().is_ok() {
         Ok(RVecData::Bool(vec![obj.extract()?]))
-    } else if let Ok(_) = obj.extract::<Idef>() {
+    } else if obj.extract::<Idef>().is_ok() {
         Ok(RVecData::Int(vec![obj.extract()?]))
-    } else if let Ok(_) = obj.extract::<Fdef>() {
+    } else if obj.downcast::<PyLong>().is_ok() {
+        warn("integer too large for int storage was converted to float");
+        Ok(RVecData::Float(vec![obj.extract()?]))
+    } else if obj.extract::<Fdef>().is_ok() {
         Ok(RVecData::Float(vec![obj.extract()?]))
-    } else if let Ok(_) = obj.extract::<String>() {
+    } else if obj.extract::<String>().is_ok() {
         Ok(RVecData::Str(vec![obj.extract()?]))
+    } else if obj.downcast::<PyComplex>().is_ok() {
+        Ok(RVecData::Complex(vec![complex_from_py(obj)]))
+    } else if let Ok(d) = obj.extract::<chrono::Duration>() {
+        let secs = [Some(timedelta_secs(d))];
+        Ok(RVecData::Duration(Duration::from_secs(&secs, auto_units(&secs))))
+    } else if obj.downcast::<PyDateTime>().is_ok() {
+        Ok(RVecData::Datetime(datetimes_from_py(&[Some(obj)])?))
+    } else if let Ok(d) = obj.extract::<NaiveDate>() {
+        Ok(RVecData::Date(vec![Some(from_date(d))]))
     } else {
-        Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>("Invalid type"))
+        Err(RVecError::Conversion(format!("cannot convert {} to an RVec", obj.get_type().name()?)).into())
     }
 }
@@ -0,0 +1,138 @@
+use std::fmt::Write;
+use chrono::{Datelike, Months, NaiveDate, format::{Item, StrftimeItems}};
+use pyo3::prelude::*;
+use pyo3::types::PyDate;
+use crate::{vec_data::{RVecData, warn}, vec_error::{RVecError, RVecResult}, Idef};
+
+/// The format dates are read and written in by default, like R
+pub const ISO_DATE: &str = "%Y-%m-%d";
+
+fn epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).expect("1970-01-01 is a valid date")
+}
+
+/// The date `days` after 1970-01-01, None if it is out of range
+pub fn to_date(days: i32) -> Option<NaiveDate> {
+    epoch().checked_add_signed(chrono::Duration::days(days as i64))
+}
+
+/// The days from 1970-01-01 to `date`, dates are stored like this like in R
+pub fn from_date(date: NaiveDate) -> i32 {
+    date.signed_duration_since(epoch()).num_days() as i32
+}
+
+/// A python date `days` after 1970-01-01, an error past the years 1 to 9999 python's dates have
+pub fn date_to_py(py: Python, days: i32) -> PyResult<PyObject> {
+    let d = to_date(days).ok_or_else(|| RVecError::Conversion("date out of range".to_string()))?;
+    Ok(PyDate::new(py, d.year(), d.month() as u8, d.day() as u8)?.into())
+}
+
+/// A number of days as an int
+#[allow(clippy::useless_conversion)]
+pub fn days_to_int(x: i32) -> Idef {
+    Idef::from(x)
+}
+
+/// An int as a number of days, None if it doesn't fit or is past the dates chrono can represent
+#[allow(clippy::useless_conversion)]
+pub fn int_to_days(x: Idef) -> Option<i32> {
+    i32::try_from(x).ok().filter(|x| to_date(*x).is_some())
+}
+
+/// Checks that `format` is a valid strftime format, formatting with an invalid one panics
+pub fn check_format(format: &str) -> RVecResult<()> {
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(RVecError::InvalidArgument(format!("invalid date format '{}'", format)));
+    }
+    Ok(())
+}
+
+/// Parses strings with a strftime `format`, warning like R if any of them can't be parsed
+pub fn parse_dates(a: &[Option<String>], format: &str) -> RVecResult<Vec<Option<i32>>> {
+    check_format(format)?;
+    let days = a.iter().map(|x| x.as_deref().and_then(|x| NaiveDate::parse_from_str(x.trim(), format).ok()).map(from_date)).collect::<Vec<_>>();
+    if days.iter().zip(a.iter()).any(|(d, x)| d.is_none() && x.is_some()) {
+        warn("NAs introduced by coercion");
+    }
+    Ok(days)
+}
+
+/// Formats dates with a strftime `format`, times and offsets in it can't be written for dates
+pub fn format_dates(a: &[Option<i32>], format: &str) -> RVecResult<Vec<Option<String>>> {
+    check_format(format)?;
+    a.iter().map(|x| x.and_then(to_date).map(|d| {
+        let mut s = String::new();
+        write!(s, "{}", d.format(format)).map_err(|_| RVecError::InvalidArgument(format!("'{}' can't format dates", format)))?;
+        Ok(s)
+    }).transpose()).collect()
+}
+
+/// The unit of a step in a sequence of dates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateUnit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// Reads a step like R's seq.Date, "month", "2 weeks" or "-1 year"
+pub fn parse_step(by: &str) -> RVecResult<(i32, DateUnit)> {
+    let by = by.trim();
+    let (n, unit) = match by.split_once(' ') {
+        Some((n, unit)) => (n.parse::<i32>().map_err(|_| invalid_step(by))?, unit.trim()),
+        None => (1, by),
+    };
+    let unit = match unit.trim_end_matches('s') {
+        "day" => DateUnit::Day,
+        "week" => DateUnit::Week,
+        "month" => DateUnit::Month,
+        "year" => DateUnit::Year,
+        _ => return Err(invalid_step(by)),
+    };
+    Ok((n, unit))
+}
+
+fn invalid_step(by: &str) -> RVecError {
+    RVecError::InvalidArgument(format!("invalid 'by' for dates '{}'", by))
+}
+
+/// The date `k` steps of `n` `unit`s after `from`. months past the end of a shorter month are
+/// moved to its last day, None if it is out of range
+fn step(from: NaiveDate, k: i64, n: i32, unit: DateUnit) -> Option<NaiveDate> {
+    let months = |m: i64| if m >= 0 {
+        from.checked_add_months(Months::new(u32::try_from(m).ok()?))
+    } else {
+        from.checked_sub_months(Months::new(u32::try_from(-m).ok()?))
+    };
+    match unit {
+        DateUnit::Day => from.checked_add_signed(chrono::Duration::days(k * n as i64)),
+        DateUnit::Week => from.checked_add_signed(chrono::Duration::weeks(k * n as i64)),
+        DateUnit::Month => months(k * n as i64),
+        DateUnit::Year => months(k * n as i64 * 12),
+    }
+}
+
+/// A sequence of dates from `from` in steps of `n` `unit`s like R's seq.Date,
+/// until `to` or for `length_out` dates
+pub fn seq_dates(from: i32, to: Option<i32>, n: i32, unit: DateUnit, length_out: Option<usize>) -> RVecResult<RVecData> {
+    let start = to_date(from).ok_or_else(|| RVecError::InvalidArgument("'from' is out of range".to_string()))?;
+    if n == 0 {
+        return Err(RVecError::InvalidArgument("'by' can't be 0".to_string()));
+    }
+    let dates = match (to, length_out) {
+        (Some(_), Some(_)) => return Err(RVecError::InvalidArgument("too many arguments to seq".to_string())),
+        (None, None) => return Err(RVecError::InvalidArgument("seq of dates needs 'to' or 'length_out'".to_string())),
+        (None, Some(len)) => (0..len as i64).map(|k| step(start, k, n, unit).map(from_date)).collect(),
+        (Some(to), None) => {
+            if (to - from).signum() * n.signum() < 0 {
+                return Err(RVecError::InvalidArgument("wrong sign in 'by' argument".to_string()));
+            }
+            (0..).map(|k| step(start, k, n, unit).map(from_date))
+                .take_while(|d| d.is_some_and(|d| if n > 0 { d <= to } else { d >= to }))
+                .collect()
+        }
+    };
+    Ok(RVecData::Date(dates))
+}
+
@@ -0,0 +1,262 @@
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use pyo3::prelude::*;
+use pyo3::types::{PyDateTime, PyDateAccess, PyTimeAccess};
+use crate::{vec_data::{RVecData, BaseRVecData, warn}, vec_date::{check_format, to_date, from_date}, vec_error::{RVecError, RVecResult}, Idef};
+
+/// The format datetimes are written in by default, ISO 8601 with the UTC offset
+pub const ISO_DATETIME: &str = "%Y-%m-%dT%H:%M:%S%.f%:z";
+
+/// ISO 8601 formats without an offset that are read when parsing without a format
+const NAIVE_FORMATS: [&str; 4] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"];
+
+const MICROS_PER_SEC: i64 = 1_000_000;
+const MICROS_PER_DAY: i64 = 86_400 * MICROS_PER_SEC;
+
+/// Points in time like R's POSIXct, stored as microseconds since 1970-01-01 00:00:00 UTC.
+/// they are shown in a fixed UTC `offset`, which is the same for the whole vector
+#[derive(Debug, Clone, PartialEq)]
+pub struct Datetime {
+    pub micros: Vec<Option<i64>>,
+    pub offset: FixedOffset,
+}
+
+impl Datetime {
+    /// Times in UTC
+    pub fn utc(micros: Vec<Option<i64>>) -> Datetime {
+        Datetime { micros, offset: FixedOffset::east_opt(0).expect("0 is a valid offset") }
+    }
+
+    /// The local date and time of each element in the vector's offset
+    pub fn local(&self) -> Vec<Option<NaiveDateTime>> {
+        self.micros.iter().map(|m| m.and_then(|m| to_local(m, self.offset))).collect()
+    }
+
+    /// Times with the local dates and times `local` in the vector's offset
+    pub fn with_local(&self, local: &[Option<NaiveDateTime>]) -> Datetime {
+        let micros = local.iter().map(|t| t.map(|t| from_local(t, self.offset))).collect();
+        Datetime { micros, offset: self.offset }
+    }
+
+    /// Formats the times in the vector's offset with a strftime `format`
+    pub fn format(&self, format: &str) -> RVecResult<Vec<Option<String>>> {
+        check_format(format)?;
+        Ok(self.micros.iter().map(|m| m.and_then(|m| to_datetime(m, self.offset)).map(|t| t.format(format).to_string())).collect())
+    }
+}
+
+/// The time `micros` after the epoch in `offset`, None if it is out of range
+pub fn to_datetime(micros: i64, offset: FixedOffset) -> Option<DateTime<FixedOffset>> {
+    DateTime::from_timestamp_micros(micros).map(|t| t.with_timezone(&offset))
+}
+
+/// The local date and time `micros` after the epoch in `offset`
+fn to_local(micros: i64, offset: FixedOffset) -> Option<NaiveDateTime> {
+    to_datetime(micros, offset).map(|t| t.naive_local())
+}
+
+/// The microseconds since the epoch of a local date and time in `offset`
+fn from_local(local: NaiveDateTime, offset: FixedOffset) -> i64 {
+    local.and_utc().timestamp_micros() - offset.local_minus_utc() as i64 * MICROS_PER_SEC
+}
+
+/// The microseconds since the epoch of midnight UTC of the date `days` after the epoch,
+/// None if they don't fit
+pub fn days_to_micros(days: i32) -> Option<i64> {
+    (days as i64).checked_mul(MICROS_PER_DAY)
+}
+
+/// The whole seconds since the epoch as an int, None if they don't fit
+#[allow(clippy::useless_conversion)]
+pub fn micros_to_secs(micros: i64) -> Option<Idef> {
+    Idef::try_from(micros.div_euclid(MICROS_PER_SEC)).ok()
+}
+
+/// A number of seconds in microseconds rounded to the nearest one, None if it is NaN or out of range
+pub fn secs_to_micros(secs: f64) -> Option<i64> {
+    let micros = (secs * MICROS_PER_SEC as f64).round();
+    if micros.is_finite() && micros.abs() < i64::MAX as f64 { Some(micros as i64) } else { None }
+}
+
+/// Reads a UTC offset like "+05:30", "-0800", "Z" or "UTC"
+pub fn parse_offset(offset: &str) -> RVecResult<FixedOffset> {
+    match offset.trim() {
+        "Z" | "UTC" => Ok(FixedOffset::east_opt(0).expect("0 is a valid offset")),
+        s => s.parse().map_err(|_| RVecError::InvalidArgument(format!("invalid UTC offset '{}'", offset))),
+    }
+}
+
+/// Parses one string with a strftime `format`, or as ISO 8601 without one. strings without an
+/// offset are local times and come back with None
+fn parse_one(x: &str, format: Option<&str>) -> Option<(NaiveDateTime, Option<FixedOffset>)> {
+    let aware = |t: DateTime<FixedOffset>| (t.naive_local(), Some(*t.offset()));
+    let midnight = |d: NaiveDate| (d.and_time(NaiveTime::MIN), None);
+    match format {
+        Some(format) => DateTime::parse_from_str(x, format).ok().map(aware)
+            .or_else(|| NaiveDateTime::parse_from_str(x, format).ok().map(|t| (t, None)))
+            .or_else(|| NaiveDate::parse_from_str(x, format).ok().map(midnight)),
+        None => DateTime::parse_from_rfc3339(x).ok().map(aware)
+            .or_else(|| NAIVE_FORMATS.iter().find_map(|f| NaiveDateTime::parse_from_str(x, f).ok()).map(|t| (t, None)))
+            .or_else(|| NaiveDate::parse_from_str(x, "%Y-%m-%d").ok().map(midnight)),
+    }
+}
+
+/// Parses strings as datetimes, warning like R if any of them can't be parsed. times without an
+/// offset are in `offset` (UTC by default), which the result is shown in. without an `offset`
+/// it is shown in the offset of the first string that has one
+pub fn parse_datetimes(a: &[Option<String>], format: Option<&str>, offset: Option<FixedOffset>) -> RVecResult<Datetime> {
+    if let Some(format) = format {
+        check_format(format)?;
+    }
+    let parsed = a.iter().map(|x| x.as_deref().and_then(|x| parse_one(x.trim(), format))).collect::<Vec<_>>();
+    if parsed.iter().zip(a.iter()).any(|(p, x)| p.is_none() && x.is_some()) {
+        warn("NAs introduced by coercion");
+    }
+    let utc = Datetime::utc(vec![]).offset;
+    let shown = offset.or_else(|| parsed.iter().flatten().find_map(|(_, o)| *o)).unwrap_or(utc);
+    let micros = parsed.iter().map(|p| p.map(|(t, o)| from_local(t, o.or(offset).unwrap_or(utc)))).collect();
+    Ok(Datetime { micros, offset: shown })
+}
+
+/// Reads a python datetime as microseconds since the epoch and its UTC offset, a datetime
+/// without a timezone is in UTC and comes back with None
+pub fn datetime_from_py(obj: &PyAny) -> PyResult<(i64, Option<FixedOffset>)> {
+    let dt: &PyDateTime = obj.downcast()?;
+    let local = NaiveDate::from_ymd_opt(dt.get_year(), dt.get_month().into(), dt.get_day().into())
+        .and_then(|d| d.and_hms_micro_opt(dt.get_hour().into(), dt.get_minute().into(), dt.get_second().into(), dt.get_microsecond()))
+        .ok_or_else(|| RVecError::Conversion("invalid or out of range datetime".to_string()))?;
+    let offset = obj.call_method0("utcoffset")?.extract::<Option<Duration>>()?
+        .map(|o| FixedOffset::east_opt(o.num_seconds() as i32).ok_or_else(|| RVecError::Conversion("UTC offset out of range".to_string())))
+        .transpose()?;
+    Ok((from_local(local, offset.unwrap_or(Datetime::utc(vec![]).offset)), offset))
+}
+
+/// Converts python datetimes, None is NA. the vector is shown in the offset of the first one
+/// that has a timezone
+pub fn datetimes_from_py(objs: &[Option<&PyAny>]) -> PyResult<Datetime> {
+    let parsed = objs.iter().map(|x| x.map(datetime_from_py).transpose()).collect::<PyResult<Vec<_>>>()?;
+    let micros = parsed.iter().map(|p| p.map(|(m, _)| m)).collect();
+    Ok(match parsed.iter().flatten().find_map(|(_, o)| *o) {
+        Some(offset) => Datetime { micros, offset },
+        None => Datetime::utc(micros),
+    })
+}
+
+/// A python datetime with a fixed offset timezone
+pub fn datetime_to_py(py: Python, micros: i64, offset: FixedOffset) -> PyResult<PyObject> {
+    let t = to_local(micros, offset).ok_or_else(|| RVecError::Conversion("datetime out of range".to_string()))?;
+    let tz = offset.to_object(py);
+    let dt = PyDateTime::new(py, t.year(), t.month() as u8, t.day() as u8, t.hour() as u8, t.minute() as u8,
+        t.second() as u8, t.nanosecond() / 1000, Some(tz.extract(py)?))?;
+    Ok(dt.into())
+}
+
+/// A part of a date or time, see `components`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    /// the day of the week from 1 for monday to 7 for sunday, like ISO 8601
+    Weekday,
+    /// the ISO 8601 week of the year, from 1 to 53
+    IsoWeek,
+}
+
+/// The local dates and times of dates or datetimes, dates are at midnight
+fn locals(data: &RVecData, op: &'static str) -> RVecResult<Vec<Option<NaiveDateTime>>> {
+    match data {
+        RVecData::Date(a) => Ok(a.iter().map(|x| x.and_then(to_date).map(|d| d.and_time(NaiveTime::MIN))).collect()),
+        RVecData::Datetime(d) => Ok(d.local()),
+        x => Err(RVecError::UnsupportedType { op, dtype: x.element_type() }),
+    }
+}
+
+/// A part of each date or datetime in its local time as ints, seconds are whole seconds
+pub fn components(data: &RVecData, component: Component) -> RVecResult<RVecData> {
+    let part = |t: NaiveDateTime| match component {
+        Component::Year => t.year() as Idef,
+        Component::Month => t.month() as Idef,
+        Component::Day => t.day() as Idef,
+        Component::Hour => t.hour() as Idef,
+        Component::Minute => t.minute() as Idef,
+        Component::Second => t.second() as Idef,
+        Component::Weekday => t.weekday().number_from_monday() as Idef,
+        Component::IsoWeek => t.iso_week().week() as Idef,
+    };
+    Ok(RVecData::Int(locals(data, "date components")?.into_iter().map(|t| t.map(part)).collect()))
+}
+
+/// The unit datetimes are truncated or rounded to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    Sec,
+    Min,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// Reads a unit like R's units, "secs", "mins", "hours", "days", "weeks", "months" or "years"
+pub fn parse_unit(unit: &str) -> RVecResult<TimeUnit> {
+    Ok(match unit.trim().trim_end_matches('s') {
+        "sec" | "second" => TimeUnit::Sec,
+        "min" | "minute" => TimeUnit::Min,
+        "hour" => TimeUnit::Hour,
+        "day" => TimeUnit::Day,
+        "week" => TimeUnit::Week,
+        "month" => TimeUnit::Month,
+        "year" => TimeUnit::Year,
+        _ => return Err(RVecError::InvalidArgument(format!("invalid time unit '{}'", unit))),
+    })
+}
+
+/// The start of the `unit` that `t` is in, weeks start on monday like ISO 8601
+fn floor_unit(t: NaiveDateTime, unit: TimeUnit) -> Option<NaiveDateTime> {
+    let date = t.date();
+    match unit {
+        TimeUnit::Sec => t.with_nanosecond(0),
+        TimeUnit::Min => t.with_nanosecond(0)?.with_second(0),
+        TimeUnit::Hour => t.with_nanosecond(0)?.with_second(0)?.with_minute(0),
+        TimeUnit::Day => Some(date.and_time(NaiveTime::MIN)),
+        TimeUnit::Week => Some((date - Duration::days(date.weekday().num_days_from_monday() as i64)).and_time(NaiveTime::MIN)),
+        TimeUnit::Month => Some(date.with_day(1)?.and_time(NaiveTime::MIN)),
+        TimeUnit::Year => Some(date.with_day(1)?.with_month(1)?.and_time(NaiveTime::MIN)),
+    }
+}
+
+/// The start of the `unit` after the one starting at `t`
+fn next_unit(t: NaiveDateTime, unit: TimeUnit) -> Option<NaiveDateTime> {
+    match unit {
+        TimeUnit::Sec => t.checked_add_signed(Duration::seconds(1)),
+        TimeUnit::Min => t.checked_add_signed(Duration::minutes(1)),
+        TimeUnit::Hour => t.checked_add_signed(Duration::hours(1)),
+        TimeUnit::Day => t.checked_add_signed(Duration::days(1)),
+        TimeUnit::Week => t.checked_add_signed(Duration::weeks(1)),
+        TimeUnit::Month => t.checked_add_months(Months::new(1)),
+        TimeUnit::Year => t.checked_add_months(Months::new(12)),
+    }
+}
+
+/// Truncates each date or datetime down to the start of its `unit` in local time like R's trunc,
+/// or rounds it to the nearest start with halves going up like R's round. dates stay dates
+pub fn trunc_time(data: &RVecData, unit: TimeUnit, round: bool) -> RVecResult<RVecData> {
+    let op = if round { "round()" } else { "trunc()" };
+    let shift = |t: NaiveDateTime| {
+        let floor = floor_unit(t, unit)?;
+        if !round {
+            return Some(floor);
+        }
+        let ceiling = next_unit(floor, unit)?;
+        Some(if t - floor >= ceiling - t { ceiling } else { floor })
+    };
+    let result = locals(data, op)?.into_iter().map(|t| t.and_then(shift)).collect::<Vec<_>>();
+    Ok(match data {
+        RVecData::Datetime(d) => RVecData::Datetime(d.with_local(&result)),
+        _ => RVecData::Date(result.iter().map(|t| t.map(|t| from_date(t.date()))).collect()),
+    })
+}
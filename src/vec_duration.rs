@@ -0,0 +1,97 @@
+use crate::{vec_data::{warn, to_f64}, vec_datetime::{TimeUnit, parse_unit, secs_to_micros}, vec_error::{RVecError, RVecResult}, Fdef};
+
+/// Lengths of time like R's difftime, the values are in `units`. only units of a fixed length,
+/// seconds to weeks, are used
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duration {
+    pub values: Vec<Option<Fdef>>,
+    pub units: TimeUnit,
+}
+
+/// The seconds in one `units`
+fn unit_secs(units: TimeUnit) -> f64 {
+    match units {
+        TimeUnit::Sec => 1.0,
+        TimeUnit::Min => 60.0,
+        TimeUnit::Hour => 3600.0,
+        TimeUnit::Day => 86_400.0,
+        TimeUnit::Week => 604_800.0,
+        TimeUnit::Month | TimeUnit::Year => unreachable!("durations only have units of a fixed length"),
+    }
+}
+
+/// The name of `units` like R, "secs", "mins", "hours", "days" or "weeks"
+pub fn unit_name(units: TimeUnit) -> &'static str {
+    match units {
+        TimeUnit::Sec => "secs",
+        TimeUnit::Min => "mins",
+        TimeUnit::Hour => "hours",
+        TimeUnit::Day => "days",
+        TimeUnit::Week => "weeks",
+        TimeUnit::Month | TimeUnit::Year => unreachable!("durations only have units of a fixed length"),
+    }
+}
+
+/// Reads the units of a duration, months and years aren't units as they have no fixed length
+pub fn parse_duration_units(units: &str) -> RVecResult<TimeUnit> {
+    match parse_unit(units)? {
+        TimeUnit::Month | TimeUnit::Year => Err(RVecError::InvalidArgument(format!("invalid units for a duration '{}'", units))),
+        units => Ok(units),
+    }
+}
+
+/// The units R's difftime picks for seconds, the largest of secs, mins, hours and days
+/// that the shortest length is at least one of
+pub fn auto_units(secs: &[Option<f64>]) -> TimeUnit {
+    let shortest = secs.iter().flatten().filter(|x| !x.is_nan()).map(|x| x.abs()).reduce(f64::min);
+    match shortest {
+        Some(x) if x >= unit_secs(TimeUnit::Day) => TimeUnit::Day,
+        Some(x) if x >= unit_secs(TimeUnit::Hour) => TimeUnit::Hour,
+        Some(x) if x >= unit_secs(TimeUnit::Min) => TimeUnit::Min,
+        _ => TimeUnit::Sec,
+    }
+}
+
+impl Duration {
+    /// Lengths of time from seconds, in `units`
+    pub fn from_secs(secs: &[Option<f64>], units: TimeUnit) -> Duration {
+        let values = secs.iter().map(|x| x.map(|x| (x / unit_secs(units)) as Fdef)).collect();
+        Duration { values, units }
+    }
+
+    /// The lengths in seconds
+    pub fn secs(&self) -> Vec<Option<f64>> {
+        self.values.iter().map(|x| x.map(|x| to_f64(x) * unit_secs(self.units))).collect()
+    }
+
+    /// The same lengths in other units
+    pub fn to_units(&self, units: TimeUnit) -> Duration {
+        if units == self.units {
+            return self.clone();
+        }
+        Duration::from_secs(&self.secs(), units)
+    }
+}
+
+/// Parses lengths like "1.5 hours" or "30 secs", warning like R if any of them can't be parsed
+pub fn parse_durations(a: &[Option<String>]) -> Vec<Option<f64>> {
+    let parse = |x: &str| {
+        let (value, units) = x.trim().split_once(' ')?;
+        Some(value.parse::<f64>().ok()? * unit_secs(parse_duration_units(units).ok()?))
+    };
+    let secs = a.iter().map(|x| x.as_deref().and_then(parse)).collect::<Vec<_>>();
+    if secs.iter().zip(a.iter()).any(|(s, x)| s.is_none() && x.is_some()) {
+        warn("NAs introduced by coercion");
+    }
+    secs
+}
+
+/// The seconds of a python timedelta
+pub fn timedelta_secs(d: chrono::Duration) -> f64 {
+    d.num_seconds() as f64 + d.subsec_nanos() as f64 / 1e9
+}
+
+/// A python timedelta of `secs` rounded to microseconds, None if it is NaN or out of range
+pub fn to_timedelta(secs: f64) -> Option<chrono::Duration> {
+    secs_to_micros(secs).map(chrono::Duration::microseconds)
+}
@@ -0,0 +1,39 @@
+use std::fmt;
+use crate::Idef;
+
+/// Errors from operations on RVecData, mapped to python exceptions in lib.rs
+#[derive(Debug, Clone, PartialEq)]
+pub enum RVecError {
+    /// an operation between two types that can't be combined, eg. "a" - 1
+    TypeMismatch { op: &'static str, lhs: &'static str, rhs: &'static str },
+    /// an operation between two vectors with lengths that can't be combined
+    LengthMismatch { lhs: usize, rhs: usize },
+    /// an index that is past the end of the vector
+    IndexOutOfRange { index: Idef, len: usize },
+    /// an index that can't be used, eg. mixing positive and negative positions
+    InvalidIndex(String),
+    /// an operation that isn't defined for the type of the vector, eg. -"a"
+    UnsupportedType { op: &'static str, dtype: &'static str },
+    /// a value that can't be converted to or from an RVec
+    Conversion(String),
+    /// an argument that has the right type but can't be used, eg. seq(1, 10, by=-1)
+    InvalidArgument(String),
+}
+
+pub type RVecResult<T> = Result<T, RVecError>;
+
+impl fmt::Display for RVecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RVecError::TypeMismatch { op, lhs, rhs } => write!(f, "unsupported types for {}: {} and {}", op, lhs, rhs),
+            RVecError::LengthMismatch { lhs, rhs } => write!(f, "mismatched lengths {} and {}", lhs, rhs),
+            RVecError::IndexOutOfRange { index, len } => write!(f, "index {} is out of range for length {}", index, len),
+            RVecError::InvalidIndex(msg) => write!(f, "{}", msg),
+            RVecError::UnsupportedType { op, dtype } => write!(f, "{} is not supported for {}", op, dtype),
+            RVecError::Conversion(msg) => write!(f, "{}", msg),
+            RVecError::InvalidArgument(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RVecError {}
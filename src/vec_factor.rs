@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use crate::{vec_data::{RVecData, BaseRVecData, warn}, vec_error::{RVecError, RVecResult}, vec_sets::VecSets, vec_sort::VecSort, vec_index::Indexing};
+
+/// Categorical data like R's factor, each element is the position of its label in `levels`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Factor {
+    pub codes: Vec<Option<u32>>,
+    pub levels: Vec<String>,
+    /// whether the levels are in order, so the elements can be compared with < and >
+    pub ordered: bool,
+}
+
+impl Factor {
+    /// A factor with the given levels from labels, labels that aren't levels become NA
+    pub fn from_labels(labels: &[Option<String>], levels: Vec<String>, ordered: bool) -> Factor {
+        let lookup = levels.iter().enumerate().map(|(i, l)| (l.as_str(), i as u32)).collect::<HashMap<_, _>>();
+        let codes = labels.iter().map(|x| x.as_deref().and_then(|x| lookup.get(x).copied())).collect();
+        Factor { codes, levels, ordered }
+    }
+
+    /// The label of each element
+    pub fn labels(&self) -> Vec<Option<String>> {
+        self.codes.iter().map(|c| c.map(|c| self.levels[c as usize].clone())).collect()
+    }
+
+    /// The codes of the labels of `value` in this factor's levels, labels that aren't
+    /// levels give NA with a warning like R
+    pub fn codes_of(&self, value: &RVecData) -> Vec<Option<u32>> {
+        let labels = match value.as_str() {
+            RVecData::Str(a) => a,
+            _ => unreachable!("as_str always gives strings"),
+        };
+        let value = Factor::from_labels(&labels, self.levels.clone(), self.ordered);
+        if value.codes.iter().zip(labels.iter()).any(|(c, x)| c.is_none() && x.is_some()) {
+            warn("invalid factor level, NA generated");
+        }
+        value.codes
+    }
+}
+
+/// The sorted distinct values of `x` as labels, the used levels in order if `x` is a factor
+fn default_levels(x: &RVecData) -> Vec<String> {
+    let levels = match x {
+        RVecData::Factor(f) => RVecData::Factor(droplevels(f)),
+        x => x.unique().and_then(|u| u.getindex(u.order(false, None)?)).unwrap_or_else(|_| x.clone()),
+    };
+    match levels {
+        RVecData::Factor(f) => f.levels,
+        levels => match levels.as_str() {
+            RVecData::Str(a) => a.into_iter().flatten().collect(),
+            _ => unreachable!("as_str always gives strings"),
+        },
+    }
+}
+
+/// Converts `x` to a factor like R's factor(). the levels are the sorted distinct values unless
+/// given, values that aren't levels become NA. `labels` renames the levels, where repeated labels
+/// merge their levels
+pub fn factor(x: &RVecData, levels: Option<&RVecData>, labels: Option<&RVecData>, ordered: bool) -> RVecResult<Factor> {
+    let levels = match levels.map(|l| l.as_str()) {
+        Some(RVecData::Str(l)) => l.into_iter().flatten().collect(),
+        _ => default_levels(x),
+    };
+    let values = match x.as_str() {
+        RVecData::Str(a) => a,
+        _ => unreachable!("as_str always gives strings"),
+    };
+    let factor = Factor::from_labels(&values, levels, ordered);
+    let labels = match labels.map(|l| l.as_str()) {
+        Some(RVecData::Str(l)) => l,
+        _ => return Ok(factor),
+    };
+    if labels.len() != factor.levels.len() {
+        return Err(RVecError::LengthMismatch { lhs: factor.levels.len(), rhs: labels.len() });
+    }
+    let labels = labels.into_iter().map(|l| l.unwrap_or_else(|| "NA".to_string())).collect::<Vec<_>>();
+    let mut merged = Vec::<String>::new();
+    let recode = labels.iter().map(|l| match merged.iter().position(|m| m == l) {
+        Some(i) => i as u32,
+        None => { merged.push(l.clone()); merged.len() as u32 - 1 }
+    }).collect::<Vec<_>>();
+    Ok(Factor { codes: factor.codes.iter().map(|c| c.map(|c| recode[c as usize])).collect(), levels: merged, ordered })
+}
+
+/// Removes the levels no element has
+pub fn droplevels(f: &Factor) -> Factor {
+    let mut used = vec![false; f.levels.len()];
+    f.codes.iter().flatten().for_each(|c| used[*c as usize] = true);
+    let levels = f.levels.iter().zip(used.iter()).filter(|(_, u)| **u).map(|(l, _)| l.clone()).collect();
+    Factor::from_labels(&f.labels(), levels, f.ordered)
+}
+
+/// Moves the level `reference` first, only for unordered factors like R
+pub fn relevel(f: &Factor, reference: &str) -> RVecResult<Factor> {
+    if f.ordered {
+        return Err(RVecError::InvalidArgument("relevel is only for unordered factors".to_string()));
+    }
+    if !f.levels.iter().any(|l| l == reference) {
+        return Err(RVecError::InvalidArgument(format!("'{}' is not a level", reference)));
+    }
+    let levels = std::iter::once(reference.to_string()).chain(f.levels.iter().filter(|l| *l != reference).cloned()).collect();
+    Ok(Factor::from_labels(&f.labels(), levels, false))
+}
+
+/// Joins factors end to end, the levels are all the levels in the order they first appear.
+/// the result is ordered if all of them are ordered with the same levels
+pub fn concat_factors(parts: &[&Factor]) -> Factor {
+    let mut levels = Vec::<String>::new();
+    for l in parts.iter().flat_map(|f| f.levels.iter()) {
+        if !levels.contains(l) {
+            levels.push(l.clone());
+        }
+    }
+    let ordered = parts.iter().all(|f| f.ordered && f.levels == levels);
+    let labels = parts.iter().flat_map(|f| f.labels()).collect::<Vec<_>>();
+    Factor::from_labels(&labels, levels, ordered)
+}
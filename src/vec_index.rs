@@ -1,26 +1,197 @@
-use crate::{RVecData};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::{RVecData, Idef, vec_data::{BaseRVecData, common_type, warn}, vec_error::{RVecError, RVecResult}, vec_factor::Factor, vec_datetime::Datetime, vec_duration::Duration};
 
-// TODO: slice
+/// How negative int indices are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NegativeIndex {
+    /// R style, -k drops the k-th element (counting from 1 like R, as -0 is just 0)
+    Exclude,
+    /// python style, -k is the k-th element from the end
+    FromEnd,
+}
+
+static NEGATIVE_FROM_END: AtomicBool = AtomicBool::new(false);
+
+/// Returns how negative indices are currently read, R style by default
+pub fn negative_index() -> NegativeIndex {
+    if NEGATIVE_FROM_END.load(Ordering::Relaxed) { NegativeIndex::FromEnd } else { NegativeIndex::Exclude }
+}
+
+/// Sets how negative indices are read for all vectors
+pub fn set_negative_index(mode: NegativeIndex) {
+    NEGATIVE_FROM_END.store(mode == NegativeIndex::FromEnd, Ordering::Relaxed);
+}
+
+/// Returns the positions selected by a python slice, from the resolved `start`, `step` and slice length
+pub fn slice_positions(start: isize, step: isize, length: isize) -> RVecData {
+    RVecData::Int((0..length).map(|i| Some((start + i * step) as Idef)).collect())
+}
+
+/// Resolves negative int indices to non-negative positions (see NegativeIndex), NA stays NA.
+/// positions past the end are kept, so the caller can decide what to do with them
+fn resolve(len: usize, index: &[Option<Idef>]) -> RVecResult<Vec<Option<Idef>>> {
+    if index.iter().flatten().all(|i| *i >= 0) {
+        return Ok(index.to_vec());
+    }
+    match negative_index() {
+        NegativeIndex::FromEnd => Ok(index.iter().map(|i| i.map(|i| if i < 0 { i + len as Idef } else { i })).collect()),
+        NegativeIndex::Exclude => {
+            if index.iter().any(|i| i.is_none_or(|i| i >= 0)) {
+                return Err(RVecError::InvalidIndex("can't mix positive, NA and negative indices".to_string()));
+            }
+            let mut keep = vec![true; len];
+            for i in index.iter().flatten() {
+                if let Some(k) = keep.get_mut((-i - 1) as usize) {
+                    *k = false;
+                }
+            }
+            Ok(keep.iter().enumerate().filter(|(_, k)| **k).map(|(i, _)| Some(i as Idef)).collect())
+        }
+    }
+}
+
+/// Takes the elements of `a` at the positions in `index`, an NA or out of range position gives NA like R
+fn take<T: Clone>(a: &[Option<T>], index: &[Option<Idef>]) -> RVecResult<Vec<Option<T>>> {
+    Ok(resolve(a.len(), index)?.iter().map(|i| match i {
+        Some(i) if *i >= 0 => a.get(*i as usize).cloned().flatten(),
+        _ => None,
+    }).collect())
+}
+
+/// Keeps the elements of `a` where `mask` is true, an NA in the mask gives NA
+fn filter<T: Clone>(a: &[Option<T>], mask: &[Option<bool>]) -> RVecResult<Vec<Option<T>>> {
+    if a.len() != mask.len() {
+        return Err(RVecError::LengthMismatch { lhs: a.len(), rhs: mask.len() });
+    }
+    Ok(a.iter().zip(mask.iter()).filter(|(_, m)| **m != Some(false)).map(|(x, m)| m.and(x.clone())).collect())
+}
+
+/// Returns the position of the first element with each of the `keys` as its name,
+/// NA if there is no such name or the vector has no names
+pub fn name_positions(names: Option<&RVecData>, keys: &[Option<String>]) -> Vec<Option<Idef>> {
+    let mut lookup = HashMap::new();
+    if let Some(RVecData::Str(names)) = names {
+        for (i, name) in names.iter().enumerate().rev() {
+            if let Some(name) = name {
+                lookup.insert(name.as_str(), i as Idef);
+            }
+        }
+    }
+    keys.iter().map(|k| k.as_deref().and_then(|k| lookup.get(k).copied())).collect()
+}
+
+/// Resolves an index to the positions it selects in a vector of length `len`, NA positions are skipped
+fn positions(len: usize, index: RVecData) -> RVecResult<Vec<usize>> {
+    match index {
+        RVecData::Int(b) => resolve(len, &b)?.iter().flatten().map(|i| {
+            if *i >= 0 && (*i as usize) < len {
+                Ok(*i as usize)
+            } else {
+                Err(RVecError::IndexOutOfRange { index: *i, len })
+            }
+        }).collect(),
+        RVecData::Bool(b) => {
+            if b.len() != len {
+                return Err(RVecError::LengthMismatch { lhs: len, rhs: b.len() });
+            }
+            Ok(b.iter().enumerate().filter(|(_, m)| **m == Some(true)).map(|(i, _)| i).collect())
+        }
+        b @ RVecData::Float(_) => positions(len, b.as_int()),
+        b => Err(RVecError::UnsupportedType { op: "indexing", dtype: b.element_type() }),
+    }
+}
+
+/// Assigns `value` to the `positions` of `a`, recycling `value` like R
+fn assign<T: Clone>(a: &mut [Option<T>], positions: &[usize], value: &[Option<T>]) -> RVecResult<()> {
+    if value.is_empty() {
+        return if positions.is_empty() { Ok(()) } else { Err(RVecError::LengthMismatch { lhs: positions.len(), rhs: 0 }) };
+    }
+    if !positions.len().is_multiple_of(value.len()) {
+        warn("number of items to replace is not a multiple of replacement length");
+    }
+    for (i, p) in positions.iter().enumerate() {
+        a[*p] = value[i % value.len()].clone();
+    }
+    Ok(())
+}
 
-pub trait Indexing {
+pub trait Indexing: Sized {
     /// bool is same length, and returns each element of a if the corresponding element of b is true
-    /// int is any length, and returns each element of a if the corresponding element of b is in range
-    fn getindex(&self, index: Self) -> Self;
+    /// int is any length, and returns the element of a at each position of b, negative positions
+    /// are read as set by `set_negative_index`. NA or out of range positions give NA in the result
+    fn getindex(&self, index: Self) -> RVecResult<Self>;
+    /// sets the elements selected by the index like `getindex` to `value`, which is recycled.
+    /// the vector is converted to the type of `value` if that is higher (see RType), a factor
+    /// keeps its levels and the labels of `value` that aren't levels become NA. numbers assigned
+    /// to a duration are in its units, and complex numbers can't be mixed with dates and times
+    fn setindex(&mut self, index: Self, value: Self) -> RVecResult<()>;
 }
 
 impl Indexing for RVecData {
-    fn getindex(&self, index: RVecData) -> Self {
+    fn getindex(&self, index: RVecData) -> RVecResult<Self> {
         match (self, index) {
-            (RVecData::Int(a), RVecData::Int(b)) => RVecData::Int(b.iter().map(|x| a[*x as usize]).collect()),
-            (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(b.iter().map(|x| a[*x as usize]).collect()),
-            (RVecData::Str(a), RVecData::Int(b)) => RVecData::Str(b.iter().map(|x| a[*x as usize].clone()).collect()),
-            (RVecData::Bool(a), RVecData::Int(b)) => RVecData::Bool(b.iter().map(|x| a[*x as usize]).collect()),
+            (RVecData::Int(a), RVecData::Int(b)) => Ok(RVecData::Int(take(a, &b)?)),
+            (RVecData::Float(a), RVecData::Int(b)) => Ok(RVecData::Float(take(a, &b)?)),
+            (RVecData::Str(a), RVecData::Int(b)) => Ok(RVecData::Str(take(a, &b)?)),
+            (RVecData::Bool(a), RVecData::Int(b)) => Ok(RVecData::Bool(take(a, &b)?)),
+            (RVecData::Complex(a), RVecData::Int(b)) => Ok(RVecData::Complex(take(a, &b)?)),
+            (RVecData::Factor(f), RVecData::Int(b)) => Ok(RVecData::Factor(Factor { codes: take(&f.codes, &b)?, ..f.clone() })),
+            (RVecData::Date(a), RVecData::Int(b)) => Ok(RVecData::Date(take(a, &b)?)),
+            (RVecData::Datetime(d), RVecData::Int(b)) => Ok(RVecData::Datetime(Datetime { micros: take(&d.micros, &b)?, offset: d.offset })),
+            (RVecData::Duration(d), RVecData::Int(b)) => Ok(RVecData::Duration(Duration { values: take(&d.values, &b)?, units: d.units })),
+
+            (RVecData::Int(a), RVecData::Bool(b)) => Ok(RVecData::Int(filter(a, &b)?)),
+            (RVecData::Float(a), RVecData::Bool(b)) => Ok(RVecData::Float(filter(a, &b)?)),
+            (RVecData::Str(a), RVecData::Bool(b)) => Ok(RVecData::Str(filter(a, &b)?)),
+            (RVecData::Bool(a), RVecData::Bool(b)) => Ok(RVecData::Bool(filter(a, &b)?)),
+            (RVecData::Complex(a), RVecData::Bool(b)) => Ok(RVecData::Complex(filter(a, &b)?)),
+            (RVecData::Factor(f), RVecData::Bool(b)) => Ok(RVecData::Factor(Factor { codes: filter(&f.codes, &b)?, ..f.clone() })),
+            (RVecData::Date(a), RVecData::Bool(b)) => Ok(RVecData::Date(filter(a, &b)?)),
+            (RVecData::Datetime(d), RVecData::Bool(b)) => Ok(RVecData::Datetime(Datetime { micros: filter(&d.micros, &b)?, offset: d.offset })),
+            (RVecData::Duration(d), RVecData::Bool(b)) => Ok(RVecData::Duration(Duration { values: filter(&d.values, &b)?, units: d.units })),
+
+            // float positions are truncated, like R
+            (a, b @ RVecData::Float(_)) => a.getindex(b.as_int()),
+            (_, b) => Err(RVecError::UnsupportedType { op: "indexing", dtype: b.element_type() }),
+        }
+    }
 
-            (RVecData::Int(a), RVecData::Bool(b)) => RVecData::Int(a.iter().zip(b.iter()).filter(|(_, x)| **x).map(|(x, _)| *x).collect()),
-            (RVecData::Float(a), RVecData::Bool(b)) => RVecData::Float(a.iter().zip(b.iter()).filter(|(_, x)| **x).map(|(x, _)| *x).collect()),
-            (RVecData::Str(a), RVecData::Bool(b)) => RVecData::Str(a.iter().zip(b.iter()).filter(|(_, x)| **x).map(|(x, _)| x.clone()).collect()),
-            (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().zip(b.iter()).filter(|(_, x)| **x).map(|(x, _)| *x).collect()),
-            _ => panic!("Invalid indexing"),
+    fn setindex(&mut self, index: RVecData, value: RVecData) -> RVecResult<()> {
+        let positions = positions(self.len(), index)?;
+        if let RVecData::Factor(f) = self {
+            let codes = f.codes_of(&value);
+            return assign(&mut f.codes, &positions, &codes);
+        }
+        if let RVecData::Duration(d) = self {
+            match &value {
+                RVecData::Duration(v) => return assign(&mut d.values, &positions, &v.to_units(d.units).values),
+                RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_) => match value.as_float() {
+                    RVecData::Float(v) => return assign(&mut d.values, &positions, &v),
+                    _ => unreachable!("as_float always gives floats"),
+                },
+                _ => (),
+            }
+        }
+        // complex numbers aren't lengths of time or points in time, so they can't be mixed with them
+        if let (RVecData::Complex(_), RVecData::Duration(_) | RVecData::Date(_) | RVecData::Datetime(_))
+            | (RVecData::Duration(_) | RVecData::Date(_) | RVecData::Datetime(_), RVecData::Complex(_)) = (&*self, &value) {
+            return Err(RVecError::TypeMismatch { op: "assignment", lhs: self.element_type(), rhs: value.element_type() });
+        }
+        let to = common_type(self.rtype(), value.rtype());
+        if self.rtype() != to {
+            *self = self.coerce(to);
+        }
+        match (self, value.coerce(to)) {
+            (RVecData::Int(a), RVecData::Int(b)) => assign(a, &positions, &b),
+            (RVecData::Float(a), RVecData::Float(b)) => assign(a, &positions, &b),
+            (RVecData::Str(a), RVecData::Str(b)) => assign(a, &positions, &b),
+            (RVecData::Bool(a), RVecData::Bool(b)) => assign(a, &positions, &b),
+            (RVecData::Complex(a), RVecData::Complex(b)) => assign(a, &positions, &b),
+            (RVecData::Date(a), RVecData::Date(b)) => assign(a, &positions, &b),
+            (RVecData::Datetime(a), RVecData::Datetime(b)) => assign(&mut a.micros, &positions, &b.micros),
+            (RVecData::Duration(a), RVecData::Duration(b)) => assign(&mut a.values, &positions, &b.to_units(a.units).values),
+            _ => unreachable!("both sides were converted to the same type"),
         }
     }
-}
\ No newline at end of file
+}
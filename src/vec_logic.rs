@@ -1,38 +1,57 @@
-use crate::RVecData;
+use std::borrow::Cow;
+use crate::{RVecData, vec_data::{BaseRVecData, map_na, zip_with, zip_na}, vec_error::{RVecError, RVecResult}};
 
-pub trait ElementLogic {
-    fn and_ew(&self, other: &Self) -> Self;
-    fn or_ew(&self, other: &Self) -> Self;
-    fn xor_ew(&self, other: &Self) -> Self;
-    fn not_ew(&self) -> Self;
+pub trait ElementLogic: Sized {
+    fn and_ew(&self, other: &Self) -> RVecResult<Self>;
+    fn or_ew(&self, other: &Self) -> RVecResult<Self>;
+    fn xor_ew(&self, other: &Self) -> RVecResult<Self>;
+    fn not_ew(&self) -> RVecResult<Self>;
 }
 
+/// Converts a vector to bools, numbers are true if not zero and strings are an error, like R
+fn logical<'a>(x: &'a RVecData, op: &'static str) -> RVecResult<Cow<'a, RVecData>> {
+    match x {
+        RVecData::Bool(_) => Ok(Cow::Borrowed(x)),
+        RVecData::Str(_) | RVecData::Complex(_) | RVecData::Factor(_) | RVecData::Date(_) | RVecData::Datetime(_) | RVecData::Duration(_) => Err(RVecError::UnsupportedType { op, dtype: x.element_type() }),
+        _ => Ok(Cow::Owned(x.as_bool())),
+    }
+}
+
+// uses R's three-valued logic, NA & FALSE = FALSE, NA | TRUE = TRUE, otherwise NA propagates
 impl ElementLogic for RVecData {
-    fn and_ew(&self, other: &Self) -> Self {
-        match (self, other) {
-            (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().zip(b.iter()).map(|(x, y)| *x && *y).collect()),
-            _ => panic!("Cannot perform element-wise AND on non-bool vectors"),
+    fn and_ew(&self, other: &Self) -> RVecResult<Self> {
+        match (logical(self, "&")?.as_ref(), logical(other, "&")?.as_ref()) {
+            (RVecData::Bool(a), RVecData::Bool(b)) => Ok(RVecData::Bool(zip_with(a, b, |x, y| match (x, y) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            })?)),
+            _ => unreachable!("logical always gives bools"),
         }
     }
 
-    fn or_ew(&self, other: &Self) -> Self {
-        match (self, other) {
-            (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().zip(b.iter()).map(|(x, y)| *x || *y).collect()),
-            _ => panic!("Cannot perform element-wise OR on non-bool vectors"),
+    fn or_ew(&self, other: &Self) -> RVecResult<Self> {
+        match (logical(self, "|")?.as_ref(), logical(other, "|")?.as_ref()) {
+            (RVecData::Bool(a), RVecData::Bool(b)) => Ok(RVecData::Bool(zip_with(a, b, |x, y| match (x, y) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            })?)),
+            _ => unreachable!("logical always gives bools"),
         }
     }
 
-    fn xor_ew(&self, other: &Self) -> Self {
-        match (self, other) {
-            (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().zip(b.iter()).map(|(x, y)| *x ^ *y).collect()),
-            _ => panic!("Cannot perform element-wise XOR on non-bool vectors"),
+    fn xor_ew(&self, other: &Self) -> RVecResult<Self> {
+        match (logical(self, "^")?.as_ref(), logical(other, "^")?.as_ref()) {
+            (RVecData::Bool(a), RVecData::Bool(b)) => Ok(RVecData::Bool(zip_na(a, b, |x, y| *x ^ *y)?)),
+            _ => unreachable!("logical always gives bools"),
         }
     }
 
-    fn not_ew(&self) -> Self {
-        match self {
-            RVecData::Bool(a) => RVecData::Bool(a.iter().map(|x| !x).collect()),
-            _ => panic!("Cannot perform element-wise NOT on non-bool vectors"),
+    fn not_ew(&self) -> RVecResult<Self> {
+        match logical(self, "~")?.as_ref() {
+            RVecData::Bool(a) => Ok(RVecData::Bool(map_na(a, |x| !x))),
+            _ => unreachable!("logical always gives bools"),
         }
     }
-}
\ No newline at end of file
+}
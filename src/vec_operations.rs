@@ -1,381 +1,272 @@
 use std::ops::{Add, Sub, Mul, Div, Neg, Rem};
-use crate::{vec_data::{RVecData, BaseRVecData}, Fdef, Idef};
+use crate::{vec_data::{RVecData, RType, BaseRVecData, coerce_pair, to_f64, map_na, map_na_checked, zip_na, zip_na_checked, zip_with}, vec_error::{RVecError, RVecResult}, vec_date::{int_to_days, to_date}, vec_datetime::{Datetime, TimeUnit, secs_to_micros},
+    vec_duration::{Duration, auto_units}, vec_complex::Complex, Idef, Fdef};
 
-// uses python adding rules. "1" + 1 = "11", "abc" + "def" = "abcdef", bool + bool = int, bool as int
-impl Add for RVecData {
-    type Output = Self;
-    fn add(self, rhs: Self) -> Self::Output {
-        if self.len() == rhs.len() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Int(a.iter().zip(b.iter()).map(|(x, y)| x + y).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x as Fdef + y).collect()),
-                // (RVec::Int(a), RVec::Str(b)) not supported
-                (RVecData::Int(a), RVecData::Bool(b)) => RVecData::Int(a.iter().zip(b.iter()).map(|(x, y)| x + *y as Idef).collect()),
-
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| x + *y as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| x + y).collect()),
-                // (RVec::Float(a), RVec::Str(b)) not supported
-                (RVecData::Float(a), RVecData::Bool(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| x + *y as Idef as Fdef).collect()),
-
-                // (RVec::Str(a), RVec::Int(b)) not supported
-                // (RVec::Str(a), RVec::Float(b)) not supported
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Str(a.iter().zip(b.iter()).map(|(x, y)| format!("{}{}", x, y)).collect()),
-                // (RVec::Str(a), RVec::Bool(b)) not supported
-
-                (RVecData::Bool(a), RVecData::Int(b)) => RVecData::Int(a.iter().zip(b.iter()).map(|(x, y)| *x as Idef + y).collect()),
-                (RVecData::Bool(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x as Idef as Fdef + y).collect()),
-                // (RVec::Bool(a), RVec::Str(b)) not supported
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Int(a.iter().zip(b.iter()).map(|(x, y)| *x as Idef + *y as Idef).collect()),
-                
-                (a, b) => panic!("Cannot add {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if self.is_scalar() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Int(b.iter().map(|x| a[0] + x).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] as Fdef + x).collect()),
-                // (RVec::Int(a), RVec::Str(b))  not supported
-                (RVecData::Int(a), RVecData::Bool(b)) => RVecData::Int(b.iter().map(|x| a[0] + *x as Idef).collect()),
-
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(b.iter().map(|x| a[0] + *x as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] + x).collect()),
-                // (RVec::Float(a), RVec::Str(b)) not supported
-                (RVecData::Float(a), RVecData::Bool(b)) => RVecData::Float(b.iter().map(|x| a[0] + *x as Idef as Fdef).collect()),
+/// repeats `s` `n` times, negative counts give an empty string
+fn repeat(s: &str, n: Idef) -> String {
+    s.repeat(n.max(0) as usize)
+}
 
-                // (RVec::Str(a), RVec::Int(b)) not supported
-                // (RVec::Str(a), RVec::Float(b)) not supported
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Str(b.iter().map(|x| format!("{}{}", a[0], x)).collect()),
-                // (RVec::Str(a), RVec::Bool(b)) not supported
+/// repeats each string in `s` by the matching count in `n`
+fn repeat_ew(s: &[Option<String>], n: &RVecData) -> RVecResult<RVecData> {
+    match n.as_int() {
+        RVecData::Int(n) => Ok(RVecData::Str(zip_na(s, &n, |x, y| repeat(x, *y))?)),
+        _ => unreachable!("as_int always gives ints"),
+    }
+}
 
-                (RVecData::Bool(a), RVecData::Int(b)) => RVecData::Int(b.iter().map(|x| a[0] as Idef + *x).collect()),
-                (RVecData::Bool(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] as Idef as Fdef + *x).collect()),
-                // (RVec::Bool(a), RVec::Str(b)) not supported
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Int(b.iter().map(|x| a[0] as Idef + *x as Idef).collect()),
+fn type_mismatch(op: &'static str, lhs: &RVecData, rhs: &RVecData) -> RVecError {
+    RVecError::TypeMismatch { op, lhs: lhs.element_type(), rhs: rhs.element_type() }
+}
 
-                (a, b) => panic!("Cannot add {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if rhs.is_scalar() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Int(a.iter().map(|x| x + b[0]).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(a.iter().map(|x| *x as Fdef + b[0]).collect()),
-                // (RVec::Int(a), RVec::Str(b)) not supported
-                (RVecData::Int(a), RVecData::Bool(b)) => RVecData::Int(a.iter().map(|x| x + b[0] as Idef).collect()),
+/// factors are categories, so like R there is no arithmetic on them, not even on their labels
+fn reject_factors(op: &'static str, lhs: &RVecData, rhs: &RVecData) -> RVecResult<()> {
+    match (lhs, rhs) {
+        (f @ RVecData::Factor(_), _) | (_, f @ RVecData::Factor(_)) => Err(RVecError::UnsupportedType { op, dtype: f.element_type() }),
+        _ => Ok(()),
+    }
+}
 
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(a.iter().map(|x| x + b[0] as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(a.iter().map(|x| x + b[0]).collect()),
-                // (RVec::Float(a), RVec::Str(b)) not supported
-                (RVecData::Float(a), RVecData::Bool(b)) => RVecData::Float(a.iter().map(|x| x + b[0] as Idef as Fdef).collect()),
+/// moves each date by the matching number of days or duration in `n` times `sign`, fractional
+/// days are truncated. dates past the range chrono can represent are NA
+fn shift_days(dates: &[Option<i32>], n: &RVecData, sign: i32) -> RVecResult<RVecData> {
+    let n = match n {
+        RVecData::Duration(d) => RVecData::Float(d.to_units(TimeUnit::Day).values).as_int(),
+        n => n.as_int(),
+    };
+    match n {
+        RVecData::Int(n) => Ok(RVecData::Date(zip_na_checked(dates, &n, |x, y| int_to_days(*y)?.checked_mul(sign)?.checked_add(*x).filter(|d| to_date(*d).is_some()))?)),
+        _ => unreachable!("as_int always gives ints"),
+    }
+}
 
-                // (RVec::Str(a), RVec::Int(b)) not supported
-                // (RVec::Str(a), RVec::Float(b)) not supported
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Str(a.iter().map(|x| format!("{}{}", x, b[0])).collect()),
-                // (RVec::Str(a), RVec::Bool(b)) not supported
+/// moves each datetime by the matching number of seconds or duration in `n` times `sign`
+fn shift_secs(d: &Datetime, n: &RVecData, sign: f64) -> RVecResult<RVecData> {
+    let secs = match n {
+        RVecData::Duration(n) => n.secs(),
+        n => match n.as_float() {
+            RVecData::Float(n) => map_na(&n, |x| to_f64(*x)),
+            _ => unreachable!("as_float always gives floats"),
+        },
+    };
+    let micros = zip_na_checked(&d.micros, &secs, |x, y| x.checked_add(secs_to_micros(y * sign)?))?;
+    Ok(RVecData::Datetime(Datetime { micros, offset: d.offset }))
+}
 
-                (RVecData::Bool(a), RVecData::Int(b)) => RVecData::Int(b.iter().map(|x| a[0] as Idef + *x).collect()),
-                (RVecData::Bool(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] as Idef as Fdef + *x).collect()),
-                // (RVec::Bool(a), RVec::Str(b)) not supported
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Int(b.iter().map(|x| a[0] as Idef + *x as Idef).collect()),
+/// applies `f` to the values of a duration and the matching numbers in `n`, which are in the
+/// duration's units, or another duration converted to them. the result is in the same units
+fn duration_zip(d: &Duration, n: &RVecData, f: impl Fn(&Fdef, &Fdef) -> Fdef) -> RVecResult<RVecData> {
+    let values = match n {
+        RVecData::Duration(n) => n.to_units(d.units).values,
+        n => match n.as_float() {
+            RVecData::Float(n) => n,
+            _ => unreachable!("as_float always gives floats"),
+        },
+    };
+    Ok(RVecData::Duration(Duration { values: zip_na(&d.values, &values, f)?, units: d.units }))
+}
 
-                (a, b) => panic!("Cannot add {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else {
-            panic!("Unsupported lengths: {:?} + {:?}", self.len(), rhs.len())
+// both operands are converted to their common type first (see RType), bools are added as ints
+// and numbers added to complex numbers are complex
+// uses python adding rules for strings. "1" + 1 = "11", "abc" + "def" = "abcdef"
+// NA in either operand gives NA, integer overflow gives NA. numbers added to dates are days,
+// numbers added to datetimes are seconds and numbers added to durations are in their units
+impl Add for RVecData {
+    type Output = RVecResult<Self>;
+    fn add(self, rhs: Self) -> Self::Output {
+        reject_factors("+", &self, &rhs)?;
+        match (&self, &rhs) {
+            (RVecData::Date(d), n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_) | RVecData::Duration(_))) => return shift_days(d, n, 1),
+            (n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_) | RVecData::Duration(_)), RVecData::Date(d)) => return shift_days(d, n, 1),
+            (RVecData::Datetime(d), n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_) | RVecData::Duration(_))) => return shift_secs(d, n, 1.0),
+            (n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_) | RVecData::Duration(_)), RVecData::Datetime(d)) => return shift_secs(d, n, 1.0),
+            (RVecData::Date(_) | RVecData::Datetime(_), RVecData::Date(_) | RVecData::Datetime(_)) => return Err(type_mismatch("+", &self, &rhs)),
+            (RVecData::Duration(d), n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_) | RVecData::Duration(_))) => return duration_zip(d, n, |x, y| x + y),
+            (n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_)), RVecData::Duration(d)) => return duration_zip(d, n, |x, y| x + y),
+            _ => (),
+        }
+        let (a, b) = coerce_pair(&self, &rhs, RType::Int);
+        match (a.as_ref(), b.as_ref()) {
+            (RVecData::Int(a), RVecData::Int(b)) => Ok(RVecData::Int(zip_na_checked(a, b, |x, y| x.checked_add(*y))?)),
+            (RVecData::Float(a), RVecData::Float(b)) => Ok(RVecData::Float(zip_na(a, b, |x, y| x + y)?)),
+            (RVecData::Complex(a), RVecData::Complex(b)) => Ok(RVecData::Complex(zip_na(a, b, |x, y| *x + *y)?)),
+            (RVecData::Str(a), RVecData::Str(b)) => Ok(RVecData::Str(zip_na(a, b, |x, y| format!("{}{}", x, y))?)),
+            _ => Err(type_mismatch("+", &self, &rhs)),
         }
     }
 }
 
-// uses python's multiplication rules, eg. "abc" * 3 = "abcabcabc", bool as int, etc.
+// uses python's multiplication rules for strings, eg. "abc" * 3 = "abcabcabc", the rest is numeric.
+// durations can be multiplied by numbers
 impl Mul for RVecData {
-    type Output = RVecData;
-
-    fn mul(self, rhs: RVecData) -> RVecData {
-        if self.len() == rhs.len() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Int(a.iter().zip(b.iter()).map(|(x, y)| x * y).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x as Fdef * *y).collect()),
-                // (RVec::Int(a), RVec::Str(b)) not supported
-                (RVecData::Int(a), RVecData::Bool(b)) => RVecData::Int(a.iter().zip(b.iter()).map(|(x, y)| x * (*y as Idef)).collect()),
-
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| x * *y as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| x * y).collect()),
-                // (RVec::Float(a), RVec::Str(b)) not supported
-                (RVecData::Float(a), RVecData::Bool(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| x * (*y as Idef as Fdef)).collect()),
-
-                // (RVec::Str(a), RVec::Int(b)) not supported
-                // (RVec::Str(a), RVec::Float(b)) not supported
-                // (RVec::Str(a), RVec::Str(b)) not supported
-                // (RVec::Str(a), RVec::Bool(b)) not supported
-                
-                (RVecData::Bool(a), RVecData::Int(b)) => RVecData::Int(a.iter().zip(b.iter()).map(|(x, y)| (*x as Idef) * y).collect()),
-                (RVecData::Bool(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| (*x as Idef as Fdef) * y).collect()),
-                // (RVec::Bool(a), RVec::Str(b)) not supported
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().zip(b.iter()).map(|(x, y)| x & y).collect()),
-                
-                (a, b) => panic!("Cannot multiply {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if self.is_scalar() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Int(a.iter().map(|x| x * b[0]).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(a.iter().map(|x| *x as Fdef * b[0]).collect()),
-                (RVecData::Int(a), RVecData::Str(b)) => RVecData::Str(a.iter().map(|x| b[0].repeat(*x as usize)).collect()),
-                (RVecData::Int(a), RVecData::Bool(b)) => RVecData::Int(a.iter().map(|x| x * b[0] as Idef).collect()),
-
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(a.iter().map(|x| x * b[0] as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(a.iter().map(|x| x * b[0]).collect()),
-                // (RVec::Float(a), RVec::Str(b)) not supported
-                (RVecData::Float(a), RVecData::Bool(b)) => RVecData::Float(a.iter().map(|x| x * b[0] as Idef as Fdef).collect()),
-
-                (RVecData::Str(a), RVecData::Int(b)) => RVecData::Str(a.iter().map(|x| x.repeat(b[0] as usize)).collect()),
-                // (RVec::Str(a), RVec::Float(b)) not supported
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Str(a.iter().map(|x| x.repeat(b[0].len())).collect()),
-                (RVecData::Str(a), RVecData::Bool(b)) => RVecData::Str(a.iter().map(|x| x.repeat(b[0] as usize)).collect()),
-
-                (RVecData::Bool(a), RVecData::Int(b)) => RVecData::Int(b.iter().map(|x| a[0] as Idef * x).collect()),
-                (RVecData::Bool(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] as Idef as Fdef * x).collect()),
-                (RVecData::Bool(a), RVecData::Str(b)) => RVecData::Str(b.iter().map(|x| x.repeat(a[0] as usize)).collect()),
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Int(b.iter().map(|x| a[0] as Idef * *x as Idef).collect()),
-
-                (a, b) => panic!("Cannot multiply {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if rhs.is_scalar() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Int(a.iter().map(|x| x * b[0]).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(a.iter().map(|x| *x as Fdef * b[0]).collect()),
-                (RVecData::Int(a), RVecData::Str(b)) => RVecData::Str(a.iter().map(|x| b[0].repeat(*x as usize)).collect()),
-                (RVecData::Int(a), RVecData::Bool(b)) => RVecData::Int(a.iter().map(|x| x * b[0] as Idef).collect()),
-
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(a.iter().map(|x| x * b[0] as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(a.iter().map(|x| x * b[0]).collect()),
-                // (RVec::Float(a), RVec::Str(b)) not supported
-                (RVecData::Float(a), RVecData::Bool(b)) => RVecData::Float(a.iter().map(|x| x * b[0] as Idef as Fdef).collect()),
-
-                (RVecData::Str(a), RVecData::Int(b)) => RVecData::Str(a.iter().map(|x| x.repeat(b[0] as usize)).collect()),
-                // (RVec::Str(a), RVec::Float(b)) not supported
-                (RVecData::Str(a), RVecData::Str(b)) => RVecData::Str(a.iter().map(|x| x.repeat(b[0].len())).collect()),
-                (RVecData::Str(a), RVecData::Bool(b)) => RVecData::Str(a.iter().map(|x| x.repeat(b[0] as usize)).collect()),
-
-                (RVecData::Bool(a), RVecData::Int(b)) => RVecData::Int(b.iter().map(|x| a[0] as Idef * x).collect()),
-                (RVecData::Bool(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] as Idef as Fdef * x).collect()),
-                // (RVec::Bool(a), RVec::Str(b)) not supported
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(a.iter().map(|x| x & b[0]).collect()),
+    type Output = RVecResult<RVecData>;
+
+    fn mul(self, rhs: RVecData) -> Self::Output {
+        reject_factors("*", &self, &rhs)?;
+        match (&self, &rhs) {
+            (RVecData::Duration(d), n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_))) => return duration_zip(d, n, |x, y| x * y),
+            (n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_)), RVecData::Duration(d)) => return duration_zip(d, n, |x, y| x * y),
+            (RVecData::Str(a), RVecData::Str(b)) => return Ok(RVecData::Str(zip_na(a, b, |x, y| x.repeat(y.len()))?)),
+            (RVecData::Str(a), n @ (RVecData::Int(_) | RVecData::Bool(_))) => return repeat_ew(a, n),
+            (n @ (RVecData::Int(_) | RVecData::Bool(_)), RVecData::Str(a)) => return repeat_ew(a, n),
+            _ => (),
+        }
 
-                (a, b) => panic!("Cannot multiply {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else {
-            panic!("mismatched lengths {} and {}", self.len(), rhs.len());
+        let (a, b) = coerce_pair(&self, &rhs, RType::Int);
+        match (a.as_ref(), b.as_ref()) {
+            (RVecData::Int(a), RVecData::Int(b)) => Ok(RVecData::Int(zip_na_checked(a, b, |x, y| x.checked_mul(*y))?)),
+            (RVecData::Float(a), RVecData::Float(b)) => Ok(RVecData::Float(zip_na(a, b, |x, y| x * y)?)),
+            (RVecData::Complex(a), RVecData::Complex(b)) => Ok(RVecData::Complex(zip_na(a, b, |x, y| *x * *y)?)),
+            _ => Err(type_mismatch("*", &self, &rhs)),
         }
     }
 }
 
+// numbers subtracted from dates are days, the difference of two dates is a duration in days.
+// for datetimes they are seconds and the units of the difference are picked like R's difftime,
+// dates subtracted from datetimes are at midnight UTC
 impl Sub for RVecData {
-    type Output = Self;
+    type Output = RVecResult<Self>;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        if self.len() == rhs.len() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Int(a.iter().zip(b.iter()).map(|(x, y)| x - y).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x as Fdef - y).collect()),
-                // (RVec::Int(a), RVec::Str(b)) not supported
-                (RVecData::Int(a), RVecData::Bool(b)) => RVecData::Int(a.iter().zip(b.iter()).map(|(x, y)| x - *y as Idef).collect()),
-
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| x - *y as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| x - y).collect()),
-                // (RVec::Float(a), RVec::Str(b)) not supported
-                (RVecData::Float(a), RVecData::Bool(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| x - *y as Idef as Fdef).collect()),
-
-                // (RVec::Str(a), RVec::Int(b)) not supported
-                // (RVec::Str(a), RVec::Float(b)) not supported
-                // (RVec::Str(a), RVec::Str(b)) not supported
-                // (RVec::Str(a), RVec::Bool(b)) not supported
-
-                (RVecData::Bool(a), RVecData::Int(b)) => RVecData::Int(a.iter().zip(b.iter()).map(|(x, y)| *x as Idef - y).collect()),
-                (RVecData::Bool(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x as Idef as Fdef - y).collect()),
-                // (RVec::Bool(a), RVec::Str(b)) not supported
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Int(a.iter().zip(b.iter()).map(|(x, y)| *x as Idef - *y as Idef).collect()),
-
-                (a, b) => panic!("Unsupported types: {:?} - {:?}", a.element_type(), b.element_type()),
+        reject_factors("-", &self, &rhs)?;
+        match (&self, &rhs) {
+            (RVecData::Date(d), n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_) | RVecData::Duration(_))) => return shift_days(d, n, -1),
+            (RVecData::Date(a), RVecData::Date(b)) => {
+                let values = zip_na_checked(a, b, |x, y| x.checked_sub(*y).map(|d| d as Fdef))?;
+                return Ok(RVecData::Duration(Duration { values, units: TimeUnit::Day }));
             }
-        } else if self.is_scalar() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Int(b.iter().map(|x| a[0] as Idef - x).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] as Fdef - x).collect()),
-                // (RVec::Int(a), RVec::Str(b)) not supported
-                (RVecData::Int(a), RVecData::Bool(b)) => RVecData::Int(b.iter().map(|x| a[0] as Idef - *x as Idef).collect()),
-
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(b.iter().map(|x| a[0] as Fdef - *x as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] as Fdef - x).collect()),
-                // (RVec::Float(a), RVec::Str(b)) not supported
-                (RVecData::Float(a), RVecData::Bool(b)) => RVecData::Float(b.iter().map(|x| a[0] as Fdef - *x as Idef as Fdef).collect()),
-
-                // (RVec::Str(a), RVec::Int(b)) not supported
-                // (RVec::Str(a), RVec::Float(b)) not supported
-                // (RVec::Str(a), RVec::Str(b)) not supported
-                // (RVec::Str(a), RVec::Bool(b)) not supported
-
-                (RVecData::Bool(a), RVecData::Int(b)) => RVecData::Int(b.iter().map(|x| a[0] as Idef - *x).collect()),
-                (RVecData::Bool(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] as Idef as Fdef - *x).collect()),
-                // (RVec::Bool(a), RVec::Str(b)) not supported
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(b.iter().map(|x| a[0] ^ *x).collect()),
-
-                (a, b) => panic!("Unsupported types: {:?} - {:?}", a.element_type(), b.element_type()),
-            }
-            
-        } else if rhs.is_scalar() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Int(a.iter().map(|x| x - b[0]).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(a.iter().map(|x| *x as Fdef - b[0]).collect()),
-                // (RVec::Int(a), RVec::Str(b)) not supported
-                (RVecData::Int(a), RVecData::Bool(b)) => RVecData::Int(a.iter().map(|x| *x - b[0] as Idef).collect()),
-
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(a.iter().map(|x| *x - b[0] as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(a.iter().map(|x| *x - b[0]).collect()),
-                // (RVec::Float(a), RVec::Str(b)) not supported
-                (RVecData::Float(a), RVecData::Bool(b)) => RVecData::Float(a.iter().map(|x| *x - b[0] as Idef as Fdef).collect()),
-
-                // (RVec::Str(a), RVec::Int(b)) not supported
-                // (RVec::Str(a), RVec::Float(b)) not supported
-                // (RVec::Str(a), RVec::Str(b)) not supported
-                // (RVec::Str(a), RVec::Bool(b)) not supported
-
-                (RVecData::Bool(a), RVecData::Int(b)) => RVecData::Int(b.iter().map(|x| a[0] as Idef - *x).collect()),
-                (RVecData::Bool(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] as Idef as Fdef - *x).collect()),
-                // (RVec::Bool(a), RVec::Str(b)) not supported
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Bool(b.iter().map(|x| a[0] ^ *x).collect()),
-
-                (a, b) => panic!("Unsupported types: {:?} - {:?}", a.element_type(), b.element_type()),
+            (RVecData::Datetime(d), n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_) | RVecData::Duration(_))) => return shift_secs(d, n, -1.0),
+            (RVecData::Duration(d), n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_) | RVecData::Duration(_))) => return duration_zip(d, n, |x, y| x - y),
+            (n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_)), RVecData::Duration(d)) => return duration_zip(d, n, |x, y| y - x),
+            _ => (),
+        }
+        let (a, b) = coerce_pair(&self, &rhs, RType::Int);
+        match (a.as_ref(), b.as_ref()) {
+            (RVecData::Int(a), RVecData::Int(b)) => Ok(RVecData::Int(zip_na_checked(a, b, |x, y| x.checked_sub(*y))?)),
+            (RVecData::Float(a), RVecData::Float(b)) => Ok(RVecData::Float(zip_na(a, b, |x, y| x - y)?)),
+            (RVecData::Complex(a), RVecData::Complex(b)) => Ok(RVecData::Complex(zip_na(a, b, |x, y| *x - *y)?)),
+            (RVecData::Datetime(a), RVecData::Datetime(b)) => {
+                let secs = zip_na_checked(&a.micros, &b.micros, |x, y| x.checked_sub(*y).map(|d| d as f64 / 1e6))?;
+                Ok(RVecData::Duration(Duration::from_secs(&secs, auto_units(&secs))))
             }
-        } else {
-            panic!("mismatched lengths {} and {}", self.len(), rhs.len())
+            _ => Err(type_mismatch("-", &self, &rhs)),
         }
     }
 }
 
+// always gives floats, or complex numbers if either side is complex, like R. durations divided by numbers are durations and durations
+// divided by durations are their ratio
 impl Div for RVecData {
-    type Output = RVecData;
-
-    fn div(self, rhs: RVecData) -> RVecData {
-        if self.len() == rhs.len() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x as Fdef / *y as Fdef).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x as Fdef / *y).collect()),
-                // (RVec::Int(a), RVec::Str(b)) not supported
-                (RVecData::Int(a), RVecData::Bool(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x as Fdef / *y as Idef as Fdef).collect()),
-
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x / *y as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x / *y).collect()),
-                // (RVec::Float(a), RVec::Str(b)) not supported
-                (RVecData::Float(a), RVecData::Bool(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x / *y as Idef as Fdef).collect()),
-
-                // (RVec::Str(a), RVec::Int(b)) not supported
-                // (RVec::Str(a), RVec::Float(b)) not supported
-                // (RVec::Str(a), RVec::Str(b)) not supported
-                // (RVec::Str(a), RVec::Bool(b)) not supported
-
-                (RVecData::Bool(a), RVecData::Int(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x as Idef as Fdef / *y as Fdef).collect()),
-                (RVecData::Bool(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x as Idef as Fdef / *y).collect()),
-                // (RVec::Bool(a), RVec::Str(b)) not supported
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x as Idef as Fdef / *y as Idef as Fdef).collect()),
-
-                (a, b) => panic!("unsupported types {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if self.is_scalar() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Float(b.iter().map(|x| a[0] as Fdef / *x as Fdef).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] as Fdef / *x).collect()),
-                // (RVec::Int(a), RVec::Str(b)) not supported
-                (RVecData::Int(a), RVecData::Bool(b)) => RVecData::Float(b.iter().map(|x| a[0] as Fdef / *x as Idef as Fdef).collect()),
-
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(b.iter().map(|x| a[0] / *x as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] / *x).collect()),
-                // (RVec::Float(a), RVec::Str(b)) not supported
-                (RVecData::Float(a), RVecData::Bool(b)) => RVecData::Float(b.iter().map(|x| a[0] / *x as Idef as Fdef).collect()),
-
-                // (RVec::Str(a), RVec::Int(b)) not supported
-                // (RVec::Str(a), RVec::Float(b)) not supported
-                // (RVec::Str(a), RVec::Str(b)) not supported
-                // (RVec::Str(a), RVec::Bool(b)) not supported
+    type Output = RVecResult<RVecData>;
+
+    fn div(self, rhs: RVecData) -> Self::Output {
+        reject_factors("/", &self, &rhs)?;
+        match (&self, &rhs) {
+            (RVecData::Duration(a), RVecData::Duration(b)) => return Ok(RVecData::Float(zip_na(&a.secs(), &b.secs(), |x, y| (x / y) as Fdef)?)),
+            (RVecData::Duration(d), n @ (RVecData::Int(_) | RVecData::Float(_) | RVecData::Bool(_))) => return duration_zip(d, n, |x, y| x / y),
+            _ => (),
+        }
+        let (a, b) = coerce_pair(&self, &rhs, RType::Float);
+        match (a.as_ref(), b.as_ref()) {
+            (RVecData::Float(a), RVecData::Float(b)) => Ok(RVecData::Float(zip_na(a, b, |x, y| x / y)?)),
+            (RVecData::Complex(a), RVecData::Complex(b)) => Ok(RVecData::Complex(zip_na(a, b, |x, y| *x / *y)?)),
+            _ => Err(type_mismatch("/", &self, &rhs)),
+        }
+    }
+}
 
-                (RVecData::Bool(a), RVecData::Int(b)) => RVecData::Float(b.iter().map(|x| a[0] as Idef as Fdef / *x as Fdef).collect()),
-                (RVecData::Bool(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] as Idef as Fdef / *x).collect()),
-                // (RVec::Bool(a), RVec::Str(b)) not supported
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Float(b.iter().map(|x| a[0] as Idef as Fdef / *x as Idef as Fdef).collect()),
+impl Neg for RVecData {
+    type Output = RVecResult<RVecData>;
 
-                (a, b) => panic!("unsupported types {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if rhs.is_scalar() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Float(a.iter().map(|x| *x as Fdef / b[0] as Fdef).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(a.iter().map(|x| *x as Fdef / b[0]).collect()),
-                // (RVec::Int(a), RVec::Str(b)) not supported
-                (RVecData::Int(a), RVecData::Bool(b)) => RVecData::Float(a.iter().map(|x| *x as Fdef / b[0] as Idef as Fdef).collect()),
+    fn neg(self) -> Self::Output {
+        match self {
+            RVecData::Int(a) => Ok(RVecData::Int(map_na(&a, |x| -*x))),
+            RVecData::Float(a) => Ok(RVecData::Float(map_na(&a, |x| -*x))),
+            RVecData::Bool(a) => Ok(RVecData::Int(map_na(&a, |x| -(*x as Idef)))),
+            RVecData::Complex(a) => Ok(RVecData::Complex(map_na(&a, |x| -*x))),
+            RVecData::Duration(d) => Ok(RVecData::Duration(Duration { values: map_na(&d.values, |x| -*x), units: d.units })),
+            a => Err(RVecError::UnsupportedType { op: "negation", dtype: a.element_type() }),
+        }
+    }
+}
 
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(a.iter().map(|x| *x / b[0] as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(a.iter().map(|x| *x / b[0]).collect()),
-                // (RVec::Float(a), RVec::Str(b)) not supported
-                (RVecData::Float(a), RVecData::Bool(b)) => RVecData::Float(a.iter().map(|x| *x / b[0] as Idef as Fdef).collect()),
+/// the remainder of `x / y` with the sign of `y`, like R's %%
+fn modulo<T: Copy + Default + PartialOrd + Add<Output = T>>(r: T, y: T) -> T {
+    let zero = T::default();
+    if r != zero && (r < zero) != (y < zero) { r + y } else { r }
+}
 
-                // (RVec::Str(a), RVec::Int(b)) not supported
-                // (RVec::Str(a), RVec::Float(b)) not supported
-                // (RVec::Str(a), RVec::Str(b)) not supported
-                // (RVec::Str(a), RVec::Bool(b)) not supported
+/// `x / y` rounded down, like R's %/%. None on overflow or division by zero
+fn floor_div(x: Idef, y: Idef) -> Option<Idef> {
+    let q = x.checked_div(y)?;
+    if x % y != 0 && (x < 0) != (y < 0) { Some(q - 1) } else { Some(q) }
+}
 
-                (RVecData::Bool(a), RVecData::Int(b)) => RVecData::Float(b.iter().map(|x| a[0] as Idef as Fdef / *x as Fdef).collect()),
-                (RVecData::Bool(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] as Idef as Fdef / *x).collect()),
-                // (RVec::Bool(a), RVec::Str(b)) not supported
-                (RVecData::Bool(a), RVecData::Bool(b)) => RVecData::Float(vec![a[0] as Idef as Fdef / b[0] as Idef as Fdef]),
+// the result has the sign of the divisor like R, so -1 %% 3 = 2. integer remainder by zero is NA
+impl Rem for RVecData {
+    type Output = RVecResult<RVecData>;
 
-                (a, b) => panic!("unsupported types {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else {
-            panic!("mismatched lengths {} and {}", self.len(), rhs.len())
+    fn rem(self, rhs: Self) -> Self::Output {
+        reject_factors("%", &self, &rhs)?;
+        let (a, b) = coerce_pair(&self, &rhs, RType::Int);
+        match (a.as_ref(), b.as_ref()) {
+            (RVecData::Int(a), RVecData::Int(b)) => Ok(RVecData::Int(zip_na_checked(a, b, |x, y| Some(modulo(x.checked_rem(*y)?, *y)))?)),
+            (RVecData::Float(a), RVecData::Float(b)) => Ok(RVecData::Float(zip_na(a, b, |x, y| modulo(x % y, *y))?)),
+            _ => Err(type_mismatch("%", &self, &rhs)),
         }
     }
 }
 
-impl Neg for RVecData {
-    type Output = RVecData;
+/// Arithmetic without an operator trait in std::ops
+pub trait ElementArith: Sized {
+    fn abs_ew(&self) -> RVecResult<Self>;
+    fn pow_ew(&self, other: &Self) -> RVecResult<Self>;
+    fn floordiv_ew(&self, other: &Self) -> RVecResult<Self>;
+}
 
-    fn neg(self) -> Self::Output {
+impl ElementArith for RVecData {
+    // integer overflow gives NA, bools become ints. complex numbers give their modulus like R
+    fn abs_ew(&self) -> RVecResult<Self> {
         match self {
-            RVecData::Int(a) => RVecData::Int(a.iter().map(|x| -*x).collect()),
-            RVecData::Float(a) => RVecData::Float(a.iter().map(|x| -*x).collect()),
-            // RVec::Str(a) not supported
-            RVecData::Bool(a) => RVecData::Int(a.iter().map(|x| -(*x as Idef)).collect()),
-
-            a => panic!("unsupported type {:?}", a.element_type()),
+            RVecData::Int(a) => Ok(RVecData::Int(map_na_checked(a, |x| x.checked_abs()))),
+            RVecData::Float(a) => Ok(RVecData::Float(map_na(a, |x| x.abs()))),
+            RVecData::Bool(a) => Ok(RVecData::Int(map_na(a, |x| *x as Idef))),
+            RVecData::Complex(a) => Ok(RVecData::Float(map_na(a, Complex::modulus))),
+            RVecData::Duration(d) => Ok(RVecData::Duration(Duration { values: map_na(&d.values, |x| x.abs()), units: d.units })),
+            a => Err(RVecError::UnsupportedType { op: "abs()", dtype: a.element_type() }),
         }
     }
-}
 
-impl Rem for RVecData {
-    type Output = RVecData;
+    // always gives floats like R, or complex numbers if either side is complex. 1 ** NA and NA ** 0 are 1
+    fn pow_ew(&self, other: &Self) -> RVecResult<Self> {
+        reject_factors("**", self, other)?;
+        let (a, b) = coerce_pair(self, other, RType::Float);
+        match (a.as_ref(), b.as_ref()) {
+            (RVecData::Float(a), RVecData::Float(b)) => Ok(RVecData::Float(zip_with(a, b, |x, y| match (x, y) {
+                (Some(x), Some(y)) => Some(x.powf(*y)),
+                (Some(x), None) if *x == 1.0 => Some(1.0),
+                (None, Some(y)) if *y == 0.0 => Some(1.0),
+                _ => None,
+            })?)),
+            (RVecData::Complex(a), RVecData::Complex(b)) => Ok(RVecData::Complex(zip_with(a, b, |x, y| match (x, y) {
+                (Some(x), Some(y)) => Some(x.powc(*y)),
+                (Some(x), None) if *x == Complex::real(1.0) => Some(Complex::real(1.0)),
+                (None, Some(y)) if *y == Complex::default() => Some(Complex::real(1.0)),
+                _ => None,
+            })?)),
+            _ => Err(type_mismatch("**", self, other)),
+        }
+    }
 
-    fn rem(self, rhs: Self) -> Self::Output {
-        if self.len() == rhs.len() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Int(a.iter().zip(b.iter()).map(|(x, y)| x % y).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x as Fdef % *y).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x % *y as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(a.iter().zip(b.iter()).map(|(x, y)| *x % *y).collect()),
-                (a, b) => panic!("unsupported types {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if rhs.is_scalar() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Int(a.iter().map(|x| x % b[0]).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(a.iter().map(|x| *x as Fdef % b[0]).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(a.iter().map(|x| *x % b[0] as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(a.iter().map(|x| *x % b[0]).collect()),
-                (a, b) => panic!("unsupported types {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else if self.is_scalar() {
-            match (self, rhs) {
-                (RVecData::Int(a), RVecData::Int(b)) => RVecData::Int(b.iter().map(|x| a[0] % x).collect()),
-                (RVecData::Int(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] as Fdef % *x).collect()),
-                (RVecData::Float(a), RVecData::Int(b)) => RVecData::Float(b.iter().map(|x| a[0] % *x as Fdef).collect()),
-                (RVecData::Float(a), RVecData::Float(b)) => RVecData::Float(b.iter().map(|x| a[0] % *x).collect()),
-                (a, b) => panic!("unsupported types {:?} and {:?}", a.element_type(), b.element_type()),
-            }
-        } else {
-            panic!("mismatched lengths {} and {}", self.len(), rhs.len())
+    // integer division by zero is NA, like R
+    fn floordiv_ew(&self, other: &Self) -> RVecResult<Self> {
+        reject_factors("//", self, other)?;
+        let (a, b) = coerce_pair(self, other, RType::Int);
+        match (a.as_ref(), b.as_ref()) {
+            (RVecData::Int(a), RVecData::Int(b)) => Ok(RVecData::Int(zip_na_checked(a, b, |x, y| floor_div(*x, *y))?)),
+            (RVecData::Float(a), RVecData::Float(b)) => Ok(RVecData::Float(zip_na(a, b, |x, y| (x / y).floor())?)),
+            _ => Err(type_mismatch("//", self, other)),
         }
     }
-}
\ No newline at end of file
+}
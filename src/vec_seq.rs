@@ -0,0 +1,78 @@
+use crate::{vec_data::RVecData, vec_error::{RVecError, RVecResult}, Fdef, Idef};
+
+/// Slack when counting the steps of a sequence, so rounding errors don't lose the last one like in R
+const FUZZ: f64 = 1e-10;
+
+fn invalid(msg: &str) -> RVecError {
+    RVecError::InvalidArgument(msg.to_string())
+}
+
+/// A sequence like R's seq, missing `from` and `to` are 1 and a missing `by` is 1 or -1.
+/// with `length_out`, two of `from`, `to` and `by` give the sequence. the result has ints if
+/// `ints` and every value is a whole number, except with `from`, `to` and no `by` or
+/// `length_out`, which gives ints when `from` is a whole number like R's from:to. a lone
+/// `from` is the end of 1:from, so seq(5) is 1 to 5 and seq(0) is 1 and 0
+pub fn seq(from: Option<f64>, to: Option<f64>, by: Option<f64>, length_out: Option<usize>, ints: bool) -> RVecResult<RVecData> {
+    if [from, to, by].iter().flatten().any(|x| !x.is_finite()) {
+        return Err(invalid("seq arguments must be finite numbers"));
+    }
+    let (mut ints, start) = (ints, from.unwrap_or(1.0));
+    let values: Vec<f64> = match (from, to, by, length_out) {
+        (Some(_), Some(_), Some(_), Some(_)) => return Err(invalid("too many arguments to seq")),
+        (Some(from), Some(to), None, Some(n)) => {
+            ints = false;
+            let by = if n > 1 { (to - from) / (n - 1) as f64 } else { 0.0 };
+            (0..n).map(|i| from + i as f64 * by).collect()
+        }
+        (None, Some(to), by, Some(n)) => (0..n).map(|i| to - (n - 1 - i) as f64 * by.unwrap_or(1.0)).collect(),
+        (_, _, by, Some(n)) => (0..n).map(|i| start + i as f64 * by.unwrap_or(1.0)).collect(),
+        (Some(from), None, None, None) => return seq(Some(1.0), Some(from), None, None, ints),
+        (_, to, None, None) => {
+            let to = to.unwrap_or(1.0);
+            ints = start.fract() == 0.0;
+            let step = if to >= start { 1.0 } else { -1.0 };
+            (0..=((to - start).abs() + FUZZ) as usize).map(|i| start + i as f64 * step).collect()
+        }
+        (_, to, Some(by), None) => {
+            let to = to.unwrap_or(1.0);
+            let n = (to - start) / by;
+            if to == start {
+                vec![start]
+            } else if !n.is_finite() {
+                return Err(invalid("invalid (to - from) / by in seq"));
+            } else if n < 0.0 {
+                return Err(invalid("wrong sign in 'by' argument"));
+            } else {
+                (0..=(n + FUZZ) as usize).map(|i| start + i as f64 * by)
+                    .map(|x| if by > 0.0 { x.min(to) } else { x.max(to) })
+                    .collect()
+            }
+        }
+    };
+    if ints && values.iter().all(|x| x.fract() == 0.0 && *x >= Idef::MIN as f64 && *x <= Idef::MAX as f64) {
+        Ok(RVecData::Int(values.into_iter().map(|x| Some(x as Idef)).collect()))
+    } else {
+        Ok(RVecData::Float(values.into_iter().map(|x| Some(x as Fdef)).collect()))
+    }
+}
+
+/// The ints from 1 to `n`
+pub fn seq_len(n: usize) -> RVecData {
+    RVecData::Int((1..=n).map(|i| Some(i as Idef)).collect())
+}
+
+/// The positions that repeat a vector of length `len` like R's rep. each element is repeated
+/// `each` times, then the whole vector `times` times, or each element by its own count if
+/// `times` has a count for each. `length_out` repeats until that length instead of `times`.
+/// an empty vector repeated to a length gives positions past its end, which read as NA
+pub fn rep_positions(len: usize, times: &[usize], each: usize, length_out: Option<usize>) -> RVecResult<Vec<usize>> {
+    let base = (0..len).flat_map(|i| std::iter::repeat_n(i, each)).collect::<Vec<_>>();
+    if let Some(n) = length_out {
+        return Ok(if base.is_empty() { vec![0; n] } else { base.iter().copied().cycle().take(n).collect() });
+    }
+    match times {
+        [t] => Ok(base.iter().copied().cycle().take(base.len() * t).collect()),
+        times if times.len() == base.len() => Ok(base.iter().zip(times).flat_map(|(i, t)| std::iter::repeat_n(*i, *t)).collect()),
+        _ => Err(invalid("invalid 'times' argument")),
+    }
+}
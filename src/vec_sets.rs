@@ -0,0 +1,123 @@
+use std::collections::{HashMap, HashSet};
+use crate::{vec_data::{RVecData, RType, coerce_pair, to_f64}, vec_error::RVecResult, vec_index::Indexing, vec_utils::concat, vec_date::days_to_int, Idef};
+
+/// Set operations and lookups, after converting both vectors to their common type (see RType),
+/// so 1 matches 1.0. NA matches NA, and NaN matches NaN, like R
+pub trait VecSets: Sized {
+    /// The elements without duplicates, in the order they first appear
+    fn unique(&self) -> RVecResult<Self>;
+    /// Whether an equal element comes before each element, or after if `from_last`
+    fn duplicated(&self, from_last: bool) -> Self;
+    fn union(&self, other: &Self) -> RVecResult<Self>;
+    fn intersect(&self, other: &Self) -> RVecResult<Self>;
+    fn setdiff(&self, other: &Self) -> RVecResult<Self>;
+    /// Whether each element is in `table`, R's %in%
+    fn is_in(&self, table: &Self) -> RVecResult<Self>;
+    /// The position of the first equal element in `table` for each element, NA if there isn't one
+    fn match_in(&self, table: &Self) -> RVecResult<Self>;
+}
+
+/// A hashable element, floats are compared by their bits with 0.0 and -0.0 and all NaNs made equal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Key<'a> {
+    NA,
+    Int(Idef),
+    Float(u64),
+    Str(&'a str),
+    Bool(bool),
+    Time(i64),
+    Complex(u64, u64),
+}
+
+fn float_bits(x: f64) -> u64 {
+    if x == 0.0 { 0.0 } else if x.is_nan() { f64::NAN } else { x }.to_bits()
+}
+
+fn float_key(x: f64) -> Key<'static> {
+    Key::Float(float_bits(x))
+}
+
+fn keys(data: &RVecData) -> Vec<Key<'_>> {
+    match data {
+        RVecData::Int(a) => a.iter().map(|x| x.map_or(Key::NA, Key::Int)).collect(),
+        RVecData::Float(a) => a.iter().map(|x| x.map_or(Key::NA, |x| float_key(to_f64(x)))).collect(),
+        RVecData::Str(a) => a.iter().map(|x| x.as_deref().map_or(Key::NA, Key::Str)).collect(),
+        RVecData::Bool(a) => a.iter().map(|x| x.map_or(Key::NA, Key::Bool)).collect(),
+        RVecData::Complex(a) => a.iter().map(|x| x.map_or(Key::NA, |x| Key::Complex(float_bits(to_f64(x.re)), float_bits(to_f64(x.im))))).collect(),
+        RVecData::Factor(f) => f.codes.iter().map(|x| x.map_or(Key::NA, |x| Key::Str(&f.levels[x as usize]))).collect(),
+        RVecData::Date(a) => a.iter().map(|x| x.map_or(Key::NA, |x| Key::Int(days_to_int(x)))).collect(),
+        RVecData::Datetime(d) => d.micros.iter().map(|x| x.map_or(Key::NA, Key::Time)).collect(),
+        // durations in other units are equal if they are the same length
+        RVecData::Duration(d) => d.secs().into_iter().map(|x| x.map_or(Key::NA, float_key)).collect(),
+    }
+}
+
+/// The elements of `data` at `positions`
+fn select(data: &RVecData, positions: impl Iterator<Item = usize>) -> RVecResult<RVecData> {
+    data.getindex(RVecData::Int(positions.map(|i| Some(i as Idef)).collect()))
+}
+
+/// The first position of each key in `table`
+fn lookup<'a>(table: &[Key<'a>]) -> HashMap<Key<'a>, usize> {
+    let mut positions = HashMap::new();
+    for (i, k) in table.iter().enumerate() {
+        positions.entry(*k).or_insert(i);
+    }
+    positions
+}
+
+/// Whether each key is in `table`
+fn found(a: &RVecData, table: &RVecData) -> Vec<bool> {
+    let table = keys(table).into_iter().collect::<HashSet<_>>();
+    keys(a).iter().map(|k| table.contains(k)).collect()
+}
+
+impl VecSets for RVecData {
+    fn unique(&self) -> RVecResult<Self> {
+        let duplicated = self.duplicated(false);
+        match duplicated {
+            RVecData::Bool(d) => select(self, d.iter().enumerate().filter(|(_, d)| **d == Some(false)).map(|(i, _)| i)),
+            _ => unreachable!("duplicated gives bools"),
+        }
+    }
+
+    fn duplicated(&self, from_last: bool) -> Self {
+        let keys = keys(self);
+        let mut seen = HashSet::new();
+        let mut duplicated = vec![Some(false); keys.len()];
+        let positions: Box<dyn Iterator<Item = usize>> = if from_last { Box::new((0..keys.len()).rev()) } else { Box::new(0..keys.len()) };
+        for i in positions {
+            duplicated[i] = Some(!seen.insert(keys[i]));
+        }
+        RVecData::Bool(duplicated)
+    }
+
+    fn union(&self, other: &Self) -> RVecResult<Self> {
+        concat(&[self, other]).unique()
+    }
+
+    // in the order of self
+    fn intersect(&self, other: &Self) -> RVecResult<Self> {
+        let (a, b) = coerce_pair(self, other, RType::Bool);
+        let found = found(&a, &b);
+        select(&a, (0..found.len()).filter(|i| found[*i]))?.unique()
+    }
+
+    fn setdiff(&self, other: &Self) -> RVecResult<Self> {
+        let (a, b) = coerce_pair(self, other, RType::Bool);
+        let found = found(&a, &b);
+        select(&a, (0..found.len()).filter(|i| !found[*i]))?.unique()
+    }
+
+    fn is_in(&self, table: &Self) -> RVecResult<Self> {
+        let (a, b) = coerce_pair(self, table, RType::Bool);
+        Ok(RVecData::Bool(found(&a, &b).into_iter().map(Some).collect()))
+    }
+
+    fn match_in(&self, table: &Self) -> RVecResult<Self> {
+        let (a, b) = coerce_pair(self, table, RType::Bool);
+        let (keys, table) = (keys(&a), keys(&b));
+        let positions = lookup(&table);
+        Ok(RVecData::Int(keys.iter().map(|k| positions.get(k).map(|i| *i as Idef)).collect()))
+    }
+}
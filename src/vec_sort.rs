@@ -0,0 +1,147 @@
+use std::cmp::Ordering;
+use pyo3::prelude::*;
+use crate::{vec_data::{RVecData, BaseRVecData}, vec_error::{RVecError, RVecResult}, Fdef, Idef};
+
+/// How rank() breaks ties, like R's ties.method
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TiesMethod {
+    /// the mean of the ranks the tied elements take up
+    Average,
+    /// the ranks in the order the elements appear
+    First,
+    /// the lowest rank for all of them
+    Min,
+    /// the highest rank for all of them
+    Max,
+    /// the lowest rank, without gaps after ties
+    Dense,
+    /// the ranks in a random order, from python's random module so random.seed() applies
+    Random,
+}
+
+/// Where rank() puts NA and NaN, like R's na.last
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NaRank {
+    /// after all other elements, in the order they appear, R's default
+    Last,
+    /// before all other elements, in the order they appear
+    First,
+    /// NA has rank NA
+    Keep,
+}
+
+/// Ordering of vectors, NA and NaN are kept apart from the rest and sorted first or last.
+/// the sorts are stable, so equal elements keep their order
+pub trait VecSort: Sized {
+    /// The positions that sort the vector, NA is dropped when `na_last` is None
+    fn order(&self, decreasing: bool, na_last: Option<bool>) -> RVecResult<Self>;
+    /// The ranks from 1, NA is ranked as set by `na`
+    fn rank(&self, ties: TiesMethod, na: NaRank) -> RVecResult<Self>;
+}
+
+/// Compares the elements at `i` and `j`, which are not NA or NaN
+fn cmp_at(data: &RVecData, i: usize, j: usize) -> Ordering {
+    match data {
+        RVecData::Int(a) => a[i].cmp(&a[j]),
+        RVecData::Float(a) => a[i].partial_cmp(&a[j]).unwrap_or(Ordering::Equal),
+        RVecData::Str(a) => a[i].cmp(&a[j]),
+        RVecData::Bool(a) => a[i].cmp(&a[j]),
+        // by the real part, then the imaginary part like R
+        RVecData::Complex(a) => a[i].map(|x| (x.re, x.im)).partial_cmp(&a[j].map(|x| (x.re, x.im))).unwrap_or(Ordering::Equal),
+        // factors sort in the order of their levels, like R
+        RVecData::Factor(f) => f.codes[i].cmp(&f.codes[j]),
+        RVecData::Date(a) => a[i].cmp(&a[j]),
+        RVecData::Datetime(d) => d.micros[i].cmp(&d.micros[j]),
+        RVecData::Duration(d) => d.values[i].partial_cmp(&d.values[j]).unwrap_or(Ordering::Equal),
+    }
+}
+
+/// Whether each element is NA or NaN
+fn missing(data: &RVecData) -> Vec<bool> {
+    match data.is_na() {
+        RVecData::Bool(a) => a.into_iter().map(|x| x == Some(true)).collect(),
+        _ => unreachable!("is_na always gives bools"),
+    }
+}
+
+/// The positions that sort by the first key, then the second for ties, and so on.
+/// all keys must have the same length
+pub fn order_keys(keys: &[&RVecData], decreasing: bool, na_last: Option<bool>) -> RVecResult<Vec<usize>> {
+    let n = keys.first().map_or(0, |k| k.len());
+    if let Some(k) = keys.iter().find(|k| k.len() != n) {
+        return Err(RVecError::LengthMismatch { lhs: n, rhs: k.len() });
+    }
+    let missing = keys.iter().map(|k| missing(k)).collect::<Vec<_>>();
+    let mut positions = (0..n)
+        .filter(|i| na_last.is_some() || !missing.iter().any(|m| m[*i]))
+        .collect::<Vec<_>>();
+    positions.sort_by(|i, j| {
+        keys.iter().zip(missing.iter()).map(|(k, m)| match (m[*i], m[*j]) {
+            (true, true) => Ordering::Equal,
+            (true, false) => if na_last == Some(false) { Ordering::Less } else { Ordering::Greater },
+            (false, true) => if na_last == Some(false) { Ordering::Greater } else { Ordering::Less },
+            (false, false) if decreasing => cmp_at(k, *j, *i),
+            (false, false) => cmp_at(k, *i, *j),
+        }).find(|o| *o != Ordering::Equal).unwrap_or(Ordering::Equal)
+    });
+    Ok(positions)
+}
+
+/// Shuffles 0..n with python's random module
+fn shuffled(n: usize) -> Vec<usize> {
+    Python::with_gil(|py| {
+        py.import("random")
+            .and_then(|random| random.call_method1("sample", ((0..n).collect::<Vec<_>>(), n)))
+            .and_then(|x| x.extract())
+            .unwrap_or_else(|_| (0..n).collect())
+    })
+}
+
+impl VecSort for RVecData {
+    fn order(&self, decreasing: bool, na_last: Option<bool>) -> RVecResult<Self> {
+        Ok(RVecData::Int(order_keys(&[self], decreasing, na_last)?.into_iter().map(|i| Some(i as Idef)).collect()))
+    }
+
+    // average gives floats, the other methods give ints
+    fn rank(&self, ties: TiesMethod, na: NaRank) -> RVecResult<Self> {
+        let positions = order_keys(&[self], false, None)?;
+        let mut ranks: Vec<Option<f64>> = vec![None; self.len()];
+        let missing = missing(self);
+        let offset = if na == NaRank::First { missing.iter().filter(|m| **m).count() } else { 0 };
+        let (mut start, mut dense) = (0, 0);
+        while start < positions.len() {
+            let end = (start..positions.len())
+                .find(|k| cmp_at(self, positions[start], positions[*k]) != Ordering::Equal)
+                .unwrap_or(positions.len());
+            dense += 1;
+            let tied = &positions[start..end];
+            let order = if ties == TiesMethod::Random { shuffled(tied.len()) } else { (0..tied.len()).collect() };
+            for (k, i) in tied.iter().enumerate() {
+                ranks[*i] = Some(offset as f64 + match ties {
+                    TiesMethod::Average => (start + end + 1) as f64 / 2.0,
+                    TiesMethod::First | TiesMethod::Random => (start + order[k] + 1) as f64,
+                    TiesMethod::Min => (start + 1) as f64,
+                    TiesMethod::Max => end as f64,
+                    TiesMethod::Dense => dense as f64,
+                });
+            }
+            start = end;
+        }
+        // NA and NaN are never tied, they take the ranks before or after the rest in order
+        let mut next = match (na, ties) {
+            (NaRank::Last, TiesMethod::Dense) => dense,
+            (NaRank::Last, _) => positions.len(),
+            (NaRank::First | NaRank::Keep, _) => 0,
+        };
+        if na != NaRank::Keep {
+            for (i, _) in missing.iter().enumerate().filter(|(_, m)| **m) {
+                next += 1;
+                ranks[i] = Some(next as f64);
+            }
+        }
+        match ties {
+            TiesMethod::Average => Ok(RVecData::Float(ranks.into_iter().map(|r| r.map(|r| r as Fdef)).collect())),
+            _ => Ok(RVecData::Int(ranks.into_iter().map(|r| r.map(|r| r as Idef)).collect())),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{vec_data::{RVecData, BaseRVecData}, vec_error::{RVecError, RVecResult}, vec_index::Indexing, vec_sets::VecSets, vec_sort::VecSort, vec_factor::Factor, Idef};
+
+/// The distinct values of `data` sorted like R's table, NA is last if `use_na` and dropped otherwise.
+/// factors have all their levels, even the ones no element has
+fn levels(data: &RVecData, use_na: bool) -> RVecResult<RVecData> {
+    if let RVecData::Factor(f) = data {
+        let mut codes = (0..f.levels.len() as u32).map(Some).collect::<Vec<_>>();
+        if use_na && f.codes.iter().any(|c| c.is_none()) {
+            codes.push(None);
+        }
+        return Ok(RVecData::Factor(Factor { codes, ..f.clone() }));
+    }
+    let unique = data.unique()?;
+    unique.getindex(unique.order(false, if use_na { Some(true) } else { None })?)
+}
+
+/// The position of each element of `data` in `levels`
+fn level_positions(data: &RVecData, levels: &RVecData) -> RVecResult<Vec<Option<usize>>> {
+    match data.match_in(levels)? {
+        RVecData::Int(a) => Ok(a.into_iter().map(|i| i.map(|i| i as usize)).collect()),
+        _ => unreachable!("match_in gives ints"),
+    }
+}
+
+/// The distinct values of `data` and how often each occurs, sorted by value, or by decreasing
+/// count if `by_count` with ties sorted by value
+pub fn table(data: &RVecData, use_na: bool, by_count: bool) -> RVecResult<(RVecData, RVecData)> {
+    let levels = levels(data, use_na)?;
+    let mut counts = vec![0 as Idef; levels.len()];
+    level_positions(data, &levels)?.into_iter().flatten().for_each(|i| counts[i] += 1);
+    let counts = RVecData::Int(counts.into_iter().map(Some).collect());
+    if by_count {
+        let order = counts.order(true, Some(true))?;
+        return Ok((levels.getindex(order.clone())?, counts.getindex(order)?));
+    }
+    Ok((levels, counts))
+}
+
+/// The values of `x`, the values of `y`, and how often each pair of them occurs, with a row for
+/// each value of `x`. `x` and `y` must have the same length
+pub fn crosstab(x: &RVecData, y: &RVecData, use_na: bool) -> RVecResult<(RVecData, RVecData, Vec<Vec<Idef>>)> {
+    if x.len() != y.len() {
+        return Err(RVecError::LengthMismatch { lhs: x.len(), rhs: y.len() });
+    }
+    let (rows, cols) = (levels(x, use_na)?, levels(y, use_na)?);
+    let mut counts = vec![vec![0 as Idef; cols.len()]; rows.len()];
+    for (i, j) in level_positions(x, &rows)?.into_iter().zip(level_positions(y, &cols)?) {
+        if let (Some(i), Some(j)) = (i, j) {
+            counts[i][j] += 1;
+        }
+    }
+    Ok((rows, cols, counts))
+}
@@ -1,43 +1,46 @@
-use crate::{Idef, Fdef};
+use std::borrow::Cow;
+use crate::{vec_data::{RVecData, RType, BaseRVecData, common_type}, vec_factor::concat_factors, vec_datetime::Datetime, vec_duration::Duration};
 
-pub fn concat_float_int(a: &Vec<Fdef>, b: &Vec<Idef>) -> Vec<Fdef> {
-    let mut result = Vec::with_capacity(a.len() + b.len());
-    result.extend(a.iter().cloned());
-    result.extend(b.iter().map(|x| *x as Fdef));
-    result
-}
-
-pub fn concat_int_float(a: &Vec<Idef>, b: &Vec<Fdef>) -> Vec<Fdef> {
-    let mut result = Vec::with_capacity(a.len() + b.len());
-    result.extend(a.iter().map(|x| *x as Fdef));
-    result.extend(b.iter().cloned());
-    result
-}
-
-pub fn concat_float_float(a: &Vec<Fdef>, b: &Vec<Fdef>) -> Vec<Fdef> {
-    let mut result = Vec::with_capacity(a.len() + b.len());
-    result.extend(a.iter().cloned());
-    result.extend(b.iter().cloned());
-    result
-}
-
-pub fn concat_int_int(a: &Vec<Idef>, b: &Vec<Idef>) -> Vec<Idef> {
-    let mut result = Vec::with_capacity(a.len() + b.len());
-    result.extend(a.iter().cloned());
-    result.extend(b.iter().cloned());
-    result
-}
-
-pub fn concat_str_str(a: &Vec<String>, b: &Vec<String>) -> Vec<String> {
-    let mut result = Vec::with_capacity(a.len() + b.len());
-    result.extend(a.iter().cloned());
-    result.extend(b.iter().cloned());
-    result
-}
-
-pub fn concat_bool_bool(a: &Vec<bool>, b: &Vec<bool>) -> Vec<bool> {
-    let mut result = Vec::with_capacity(a.len() + b.len());
-    result.extend(a.iter().cloned());
-    result.extend(b.iter().cloned());
+/// Joins the vectors end to end after converting them to their common type (see RType),
+/// no vectors give an empty bool vector. factors are joined with all their levels
+pub fn concat(parts: &[&RVecData]) -> RVecData {
+    let factors = parts.iter().filter_map(|x| match x { RVecData::Factor(f) => Some(f), _ => None }).collect::<Vec<_>>();
+    if !parts.is_empty() && factors.len() == parts.len() {
+        return RVecData::Factor(concat_factors(&factors));
+    }
+    let to = parts.iter().map(|x| x.rtype()).reduce(common_type).unwrap_or(RType::Bool);
+    let parts = parts.iter().map(|x| if x.rtype() == to { Cow::Borrowed(*x) } else { Cow::Owned(x.coerce(to)) }).collect::<Vec<_>>();
+    let mut result = match to {
+        RType::Bool => RVecData::Bool(vec![]),
+        RType::Int => RVecData::Int(vec![]),
+        RType::Float => RVecData::Float(vec![]),
+        RType::Complex => RVecData::Complex(vec![]),
+        RType::Str => RVecData::Str(vec![]),
+        RType::Date => RVecData::Date(vec![]),
+        // in the units of the first part, like R
+        RType::Duration => match parts[0].as_ref() {
+            RVecData::Duration(d) => RVecData::Duration(Duration { values: vec![], units: d.units }),
+            _ => unreachable!("all parts are converted to the same type"),
+        },
+        // shown in the offset of the first part, like R's time zone
+        RType::Datetime => match parts[0].as_ref() {
+            RVecData::Datetime(d) => RVecData::Datetime(Datetime { micros: vec![], offset: d.offset }),
+            _ => unreachable!("all parts are converted to the same type"),
+        },
+        RType::Factor => unreachable!("factors are joined with concat_factors"),
+    };
+    for part in parts {
+        match (&mut result, part.as_ref()) {
+            (RVecData::Bool(a), RVecData::Bool(b)) => a.extend_from_slice(b),
+            (RVecData::Int(a), RVecData::Int(b)) => a.extend_from_slice(b),
+            (RVecData::Float(a), RVecData::Float(b)) => a.extend_from_slice(b),
+            (RVecData::Complex(a), RVecData::Complex(b)) => a.extend_from_slice(b),
+            (RVecData::Str(a), RVecData::Str(b)) => a.extend_from_slice(b),
+            (RVecData::Date(a), RVecData::Date(b)) => a.extend_from_slice(b),
+            (RVecData::Datetime(a), RVecData::Datetime(b)) => a.micros.extend_from_slice(&b.micros),
+            (RVecData::Duration(a), RVecData::Duration(b)) => a.values.extend(b.to_units(a.units).values),
+            _ => unreachable!("all parts are converted to the same type"),
+        }
+    }
     result
 }
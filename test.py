@@ -1,3 +1,5 @@
+import datetime
+import math
 import unittest
 import rvec
 
@@ -61,5 +63,689 @@ class TestRVec(unittest.TestCase):
         r4 = r1 / 2
         self.assertEqual(list(r4), [0.5, 1, 1.5])
 
+    def test_na(self):
+        # Test that None is read in as NA and given back as None
+        r1 = rvec.RVec([1, None, 3])
+        self.assertEqual(r1.to_list(), [1, None, 3])
+        self.assertEqual(r1.is_na().to_list(), [False, True, False])
+        self.assertEqual(str(r1), "[1, NA, 3]")
+
+        # Test that NA propagates through operations
+        r2 = rvec.RVec([1, 2, None])
+        self.assertEqual((r1 + r2).to_list(), [2, None, None])
+        self.assertEqual((r1 * 2).to_list(), [2, None, 6])
+        self.assertEqual((-r1).to_list(), [-1, None, -3])
+        self.assertEqual(rvec.RVec(["a", None]).str_upper().to_list(), ["A", None])
+
+        # Test R's three-valued logic
+        b1 = rvec.RVec([None, None, None])
+        b2 = rvec.RVec([True, False, None])
+        self.assertEqual((b1 & b2).to_list(), [None, False, None])
+        self.assertEqual((b1 | b2).to_list(), [True, None, None])
+        self.assertEqual((~b2).to_list(), [False, True, None])
+
+    def test_errors(self):
+        # Test that errors are raised as python exceptions instead of panics
+        r1 = rvec.RVec([1, 2, 3])
+        with self.assertRaises(TypeError):
+            r1 - rvec.RVec(["a", "b", "c"])
+        with self.assertRaises(TypeError):
+            -rvec.RVec(["a"])
+        with self.assertRaises(TypeError):
+            rvec.RVec(object())
+        with self.assertRaises(ValueError):
+            r1[rvec.RVec([True, False])]
+        with self.assertRaises(IndexError):
+            r1[rvec.RVec([1, -1])]
+
+    def test_compare(self):
+        # Test comparing RVecs, scalars and lists with operators
+        r1 = rvec.RVec([1, 2, 3])
+        r2 = rvec.RVec([3, 2, 1])
+        self.assertEqual((r1 == r2).to_list(), [False, True, False])
+        self.assertEqual((r1 != r2).to_list(), [True, False, True])
+        self.assertEqual((r1 < r2).to_list(), [True, False, False])
+        self.assertEqual((r1 >= 2).to_list(), [False, True, True])
+        self.assertEqual((2 < r1).to_list(), [False, False, True])
+        self.assertEqual((r1 <= [1, 1, 1]).to_list(), [True, False, False])
+
+        # Test indexing with the result of a comparison
+        self.assertEqual(r1[r1 > 1].to_list(), [2, 3])
+
+    def test_recycling(self):
+        # Test that the shorter operand is recycled
+        r1 = rvec.RVec([1, 2, 3, 4])
+        r2 = rvec.RVec([10, 20])
+        self.assertEqual((r1 + r2).to_list(), [11, 22, 13, 24])
+        self.assertEqual((r2 * r1).to_list(), [10, 40, 30, 80])
+        self.assertEqual((r1 > r2 - 9).to_list(), [False, False, True, False])
+        self.assertEqual((rvec.RVec(["a", "b"]) + rvec.RVec(["x", "y", "z", "w"])).to_list(), ["ax", "by", "az", "bw"])
+        self.assertEqual((rvec.RVec([True, False]) & rvec.RVec([True, True, False, True])).to_list(), [True, False, False, False])
+
+        # Test that a warning is given when the lengths are not multiples
+        with self.assertWarns(UserWarning):
+            r3 = r1 + rvec.RVec([1, 2, 3])
+        self.assertEqual(r3.to_list(), [2, 4, 6, 5])
+
+        # Test that an empty operand gives an empty result
+        self.assertEqual((r1 + rvec.RVec([])).to_list(), [])
+
+    def test_coercion(self):
+        # Test that mixed types are converted to the higher of bool < int < float < str
+        i = rvec.RVec([1, 2])
+        f = rvec.RVec([0.5, 1.5])
+        s = rvec.RVec(["a", "b"])
+        b = rvec.RVec([True, False])
+        self.assertEqual((i + b).to_list(), [2, 2])
+        self.assertEqual((b + f).to_list(), [1.5, 1.5])
+        self.assertEqual((s + i).to_list(), ["a1", "b2"])
+        self.assertEqual((b + s).to_list(), ["TRUEa", "FALSEb"])
+        self.assertEqual((i == b).to_list(), [True, False])
+        self.assertEqual((i == rvec.RVec(["1", "3"])).to_list(), [True, False])
+        self.assertEqual((i & rvec.RVec([0, 3])).to_list(), [False, True])
+        self.assertEqual(rvec.RVec(["1", "x"]).__add__(1.5).to_list(), ["11.5", "x1.5"])
+
+        # Test that strings can't be used in numeric operations
+        with self.assertRaises(TypeError):
+            s / i
+        with self.assertRaises(TypeError):
+            s & b
+
+    def test_precision(self):
+        # Test that large ints and precise floats are kept
+        r1 = rvec.RVec([2**40, 1])
+        self.assertEqual((r1 + 1).to_list(), [2**40 + 1, 2])
+        r2 = rvec.RVec([0.1, 0.2])
+        self.assertEqual((r2 + 0.7).to_list(), [0.1 + 0.7, 0.2 + 0.7])
+
+        # Test that ints too large for int storage become floats with a warning
+        with self.assertWarns(UserWarning):
+            r3 = rvec.RVec([2**70, 1])
+        self.assertEqual(r3.to_list(), [float(2**70), 1.0])
+
+    def test_slice(self):
+        # Test indexing with python slices
+        r = rvec.RVec([1.0, 2.0, 3.0, 4.0, 5.0])
+        self.assertEqual(r[1:3].to_list(), [2.0, 3.0])
+        self.assertEqual(r[::2].to_list(), [1.0, 3.0, 5.0])
+        self.assertEqual(r[-2:].to_list(), [4.0, 5.0])
+        self.assertEqual(r[::-1].to_list(), [5.0, 4.0, 3.0, 2.0, 1.0])
+        self.assertEqual(r[3:1:-1].to_list(), [4.0, 3.0])
+        self.assertEqual(r[10:].to_list(), [])
+        self.assertEqual(rvec.RVec(["a", "b", None])[1:].to_list(), ["b", None])
+        self.assertEqual(rvec.RVec([True, False, True])[:-1].to_list(), [True, False])
+
+    def test_setitem(self):
+        # Test assigning with a mask and a scalar
+        r = rvec.RVec([1, -2, 3, -4])
+        r[r < 0] = 0
+        self.assertEqual(r.to_list(), [1, 0, 3, 0])
+
+        # Test assigning with int positions and a list
+        r[[0, 2]] = [10, 20]
+        self.assertEqual(r.to_list(), [10, 0, 20, 0])
+
+        # Test assigning with a slice and a recycled value
+        r[::2] = rvec.RVec([7])
+        self.assertEqual(r.to_list(), [7, 0, 7, 0])
+        r[:] = [1, 2]
+        self.assertEqual(r.to_list(), [1, 2, 1, 2])
+
+        # Test that the vector is promoted to the type of the value
+        r[1] = 2.5
+        self.assertEqual(r.to_list(), [1.0, 2.5, 1.0, 2.0])
+        r[rvec.RVec([0])] = None
+        self.assertEqual(r.to_list(), [None, 2.5, 1.0, 2.0])
+
+        r[[]] = []
+        self.assertEqual(r.to_list(), [None, 2.5, 1.0, 2.0])
+        with self.assertWarns(UserWarning):
+            r[:] = [1, 2, 3]
+        with self.assertRaises(IndexError):
+            r[10] = 1
+
+    def test_negative_index(self):
+        r = rvec.RVec([1, 2, 3, 4])
+
+        # Test R style negative indices, -k drops the k-th element
+        self.assertEqual(rvec.get_negative_index(), "exclude")
+        self.assertEqual(r[[-1, -2]].to_list(), [3, 4])
+        self.assertEqual(r[-4].to_list(), [1, 2, 3])
+        with self.assertRaises(IndexError):
+            r[[0, -1]]
+
+        # Test that positions past the end give NA
+        self.assertEqual(r[[3, 4]].to_list(), [4, None])
+
+        # Test that an empty index selects nothing
+        self.assertEqual(r[[]].to_list(), [])
+
+        # Test python style negative indices
+        rvec.set_negative_index("from_end")
+        try:
+            self.assertEqual(r[[-1, 0]].to_list(), [4, 1])
+            self.assertEqual(r[-5].to_list(), [None])
+            r[-1] = 10
+            self.assertEqual(r.to_list(), [1, 2, 3, 10])
+        finally:
+            rvec.set_negative_index("exclude")
+
+    def test_names(self):
+        r = rvec.RVec({"a": 1, "b": 2, "c": 3})
+
+        # Test construction from a dict and with names=
+        self.assertEqual(r.names, ["a", "b", "c"])
+        self.assertEqual(rvec.RVec([1, 2], names=["x", "y"]).names, ["x", "y"])
+        self.assertIsNone(rvec.RVec([1, 2]).names)
+        with self.assertRaises(ValueError):
+            rvec.RVec([1, 2], names=["x"])
+
+        # Test that names are kept by operations
+        self.assertEqual((r + 1).names, ["a", "b", "c"])
+        self.assertEqual((1 + r).names, ["a", "b", "c"])
+        self.assertEqual((rvec.RVec([1, 2, 3]) * r).names, ["a", "b", "c"])
+        self.assertEqual(r.str().str_upper().names, ["a", "b", "c"])
+
+        # Test indexing by name
+        self.assertEqual(r["b"].to_list(), [2])
+        self.assertEqual(r[["c", "a"]].names, ["c", "a"])
+        self.assertEqual(r[["z"]].to_list(), [None])
+        self.assertEqual(r[1:].names, ["b", "c"])
+        r["b"] = 20
+        self.assertEqual(r.to_list(), [1, 20, 3])
+        with self.assertRaises(IndexError):
+            r["z"] = 1
+
+        # Test printing
+        self.assertEqual(str(r), "[a=1, b=20, c=3]")
+        self.assertEqual(repr(r), "RVec([a=1, b=20, c=3])")
+        r.names = None
+        self.assertEqual(str(r), "[1, 20, 3]")
+
+    def test_math(self):
+        r = rvec.RVec([1, 4, None, 9])
+
+        # Test that ints and bools are promoted to floats and NA stays NA
+        self.assertEqual(r.sqrt().to_list(), [1.0, 2.0, None, 3.0])
+        self.assertEqual(rvec.RVec([True, False]).exp().to_list(), [math.e, 1.0])
+        self.assertEqual(rvec.RVec([1, 100]).log(10).to_list(), [0.0, 2.0])
+        self.assertEqual(rvec.RVec([1.0]).log().to_list(), [0.0])
+        self.assertAlmostEqual(rvec.RVec([1e-10]).log1p().to_list()[0], 1e-10)
+        self.assertEqual(rvec.RVec([0.0]).sin().to_list(), [0.0])
+        self.assertTrue(math.isnan(rvec.RVec([float("nan")]).cos().to_list()[0]))
+
+        # Test that NaNs warn like R
+        with self.assertWarns(UserWarning):
+            self.assertTrue(math.isnan(rvec.RVec([-1]).sqrt().to_list()[0]))
+
+        # Test rounding
+        x = rvec.RVec([-1.5, 2.567, 1234.5])
+        self.assertEqual(x.floor().to_list(), [-2.0, 2.0, 1234.0])
+        self.assertEqual(x.ceiling().to_list(), [-1.0, 3.0, 1235.0])
+        self.assertEqual(x.trunc().to_list(), [-1.0, 2.0, 1234.0])
+        self.assertEqual(x.round(1).to_list(), [-1.5, 2.6, 1234.5])
+        self.assertEqual(x.round(-2).to_list(), [-0.0, 0.0, 1200.0])
+        self.assertEqual(rvec.RVec([0.5, 1.5, 2.5, -0.5]).round().to_list(), [0.0, 2.0, 2.0, -0.0])
+        self.assertEqual(rvec.RVec([15, 25]).round(-1).to_list(), [20.0, 20.0])
+        self.assertEqual(rvec.RVec([0.15, 0.35, -0.15]).round(1).to_list(), [0.1, 0.3, -0.1])
+        self.assertEqual(rvec.RVec([2.675]).round(2).to_list(), [2.67])
+        self.assertEqual(x.signif(2).to_list(), [-1.5, 2.6, 1200.0])
+
+        with self.assertRaises(TypeError):
+            rvec.RVec(["a"]).sqrt()
+
+    def test_arith(self):
+        r = rvec.RVec([-5, 5, None])
+
+        # Test abs
+        self.assertEqual(abs(r).to_list(), [5, 5, None])
+        self.assertEqual(abs(rvec.RVec([-1.5])).to_list(), [1.5])
+
+        # Test that powers are always floats, with R's 1 ** NA and NA ** 0
+        self.assertEqual((rvec.RVec([2, 3]) ** 2).to_list(), [4.0, 9.0])
+        self.assertEqual((2 ** rvec.RVec([1, 3])).to_list(), [2.0, 8.0])
+        self.assertEqual((rvec.RVec([1, None]) ** rvec.RVec([None, 0])).to_list(), [1.0, 1.0])
+        with self.assertRaises(TypeError):
+            pow(rvec.RVec([2, 3]), 2, 3)
+
+        # Test that modulo has the sign of the divisor and floor division rounds down
+        self.assertEqual((r % 3).to_list(), [1, 2, None])
+        self.assertEqual((r % -3).to_list(), [-2, -1, None])
+        self.assertEqual((r // 3).to_list(), [-2, 1, None])
+        self.assertEqual((rvec.RVec([-5.5]) % 2).to_list(), [0.5])
+        self.assertEqual((rvec.RVec([-5.5]) // 2).to_list(), [-3.0])
+        self.assertEqual((7 // rvec.RVec([2])).to_list(), [3])
+        self.assertEqual((7 % rvec.RVec([2])).to_list(), [1])
+        self.assertEqual((r // 0).to_list(), [None, None, None])
+
+        # Test divmod
+        q, m = divmod(rvec.RVec([7, -7]), 2)
+        self.assertEqual(q.to_list(), [3, -4])
+        self.assertEqual(m.to_list(), [1, 1])
+
+    def test_aggregate(self):
+        r = rvec.RVec([3, 1, None, 2])
+
+        # Test that NA gives NA unless it is removed
+        self.assertEqual(r.sum().to_list(), [None])
+        self.assertEqual(r.sum(na_rm=True).to_list(), [6])
+        self.assertEqual(r.prod(na_rm=True).to_list(), [6.0])
+        self.assertEqual(r.mean(na_rm=True).to_list(), [2.0])
+        self.assertEqual(r.min(na_rm=True).to_list(), [1])
+        self.assertEqual(r.max(na_rm=True).to_list(), [3])
+        self.assertEqual(r.range(na_rm=True).to_list(), [1, 3])
+        self.assertEqual(r.var(na_rm=True).to_list(), [1.0])
+        self.assertEqual(r.sd(na_rm=True).to_list(), [1.0])
+        self.assertEqual(r.median(na_rm=True).to_list(), [2.0])
+        self.assertEqual(rvec.RVec([4, 1, 3, 2]).median().to_list(), [2.5])
+
+        # Test that bools count as 0 and 1 and int sums don't overflow
+        self.assertEqual(rvec.RVec([True, True, False]).sum().to_list(), [2])
+        self.assertEqual(rvec.RVec([True, False]).mean().to_list(), [0.5])
+        self.assertEqual(rvec.RVec([2 ** 62, 2 ** 62]).sum().to_list(), [2.0 ** 63])
+
+        # Test NaN, strings and empty vectors
+        self.assertTrue(math.isnan(rvec.RVec([1.0, float("nan")]).max().to_list()[0]))
+        self.assertEqual(rvec.RVec([1.0, float("nan")]).max(na_rm=True).to_list(), [1.0])
+        self.assertEqual(rvec.RVec(["b", "a"]).range().to_list(), ["a", "b"])
+        self.assertEqual(rvec.RVec([1]).var().to_list(), [None])
+        with self.assertWarns(UserWarning):
+            self.assertEqual(rvec.RVec([None]).min(na_rm=True).to_list(), [math.inf])
+        with self.assertRaises(TypeError):
+            rvec.RVec(["a"]).sum()
+
+    def test_accurate_sum(self):
+        # Test that float sums don't lose the small values
+        r = rvec.RVec([1e16] + [1.0] * 10000 + [-1e16])
+        self.assertEqual(r.sum().to_list(), [10000.0])
+        self.assertEqual(rvec.RVec([0.1] * 1000000).sum().to_list()[0], math.fsum([0.1] * 1000000))
+        self.assertEqual(rvec.RVec([0.1] * 10).mean().to_list(), [0.1])
+        self.assertEqual(rvec.RVec([1e9 + 1, 1e9 + 2, 1e9 + 3]).var().to_list(), [1.0])
+        self.assertEqual(rvec.RVec([math.inf, 1.0]).sum().to_list(), [math.inf])
+
+    def test_cumulative(self):
+        r = rvec.RVec([1, 3, None, 2])
+
+        # Test that NA propagates from the first NA
+        self.assertEqual(r.cumsum().to_list(), [1, 4, None, None])
+        self.assertEqual(r.cumprod().to_list(), [1.0, 3.0, None, None])
+        self.assertEqual(r.cummax().to_list(), [1, 3, None, None])
+        self.assertEqual(r.cummin().to_list(), [1, 1, None, None])
+
+        # Test types, NaN and int overflow
+        self.assertEqual(rvec.RVec([True, True, False]).cumsum().to_list(), [1, 2, 2])
+        self.assertEqual(rvec.RVec([2.5, 1.0, 3.0]).cummin().to_list(), [2.5, 1.0, 1.0])
+        self.assertEqual(rvec.RVec([0.1] * 3).cumsum().to_list(), [0.1, 0.2, 0.30000000000000004])
+        x = rvec.RVec([1.0, float("nan"), 2.0]).cummax().to_list()
+        self.assertTrue(math.isnan(x[1]) and math.isnan(x[2]))
+        self.assertEqual(rvec.RVec([2 ** 62, 2 ** 62]).cumsum().to_list(), [2.0 ** 62, 2.0 ** 63])
+        with self.assertRaises(TypeError):
+            rvec.RVec(["a"]).cumsum()
+
+    def test_sort(self):
+        r = rvec.RVec([3, None, 1, 2])
+
+        # Test sort, NA is dropped unless na_last is given
+        self.assertEqual(r.sort().to_list(), [1, 2, 3])
+        self.assertEqual(r.sort(decreasing=True, na_last=True).to_list(), [3, 2, 1, None])
+        self.assertEqual(r.sort(na_last=False).to_list(), [None, 1, 2, 3])
+        self.assertEqual(rvec.RVec({"a": 2, "b": 1}).sort().names, ["b", "a"])
+        self.assertEqual(rvec.RVec(["b", "B", "a"]).sort().to_list(), ["B", "a", "b"])
+
+        # Test order gives positions usable as an index
+        self.assertEqual(r.order().to_list(), [2, 3, 0, 1])
+        self.assertEqual(r[r.order(na_last=None)].to_list(), [1, 2, 3])
+
+        # Test ordering by several keys is stable
+        x = rvec.RVec([1, 1, 0, 1])
+        y = rvec.RVec(["b", "a", "c", "a"])
+        self.assertEqual(rvec.order(x, y).to_list(), [2, 1, 3, 0])
+        self.assertEqual(rvec.order(x, decreasing=True).to_list(), [0, 1, 3, 2])
+        with self.assertRaises(ValueError):
+            rvec.order(x, rvec.RVec([1]))
+
+        # Test ranks with ties
+        t = rvec.RVec([20, 10, 20, None, 30])
+        self.assertEqual(t.rank().to_list(), [2.5, 1.0, 2.5, 5.0, 4.0])
+        self.assertEqual(t.rank("first").to_list(), [2, 1, 3, 5, 4])
+        self.assertEqual(t.rank("min").to_list(), [2, 1, 2, 5, 4])
+        self.assertEqual(t.rank("max").to_list(), [3, 1, 3, 5, 4])
+        self.assertEqual(t.rank("dense").to_list(), [2, 1, 2, 4, 3])
+        self.assertIn(t.rank("random").to_list(), [[2, 1, 3, 5, 4], [3, 1, 2, 5, 4]])
+
+        # Test where NA is ranked
+        self.assertEqual(t.rank(na_last=False).to_list(), [3.5, 2.0, 3.5, 1.0, 5.0])
+        self.assertEqual(t.rank("min", na_last="keep").to_list(), [2, 1, 2, None, 4])
+        with self.assertRaises(ValueError):
+            t.rank("mean")
+
+    def test_sets(self):
+        r = rvec.RVec([3, 1, None, 3, 1.0, None])
+
+        # Test unique and duplicated, NA is a value like any other
+        self.assertEqual(r.unique().to_list(), [3.0, 1.0, None])
+        self.assertEqual(r.duplicated().to_list(), [False, False, False, True, True, True])
+        self.assertEqual(r.duplicated(from_last=True).to_list(), [True, True, True, False, False, False])
+        self.assertEqual(rvec.RVec(["a", "b", "a"]).unique().to_list(), ["a", "b"])
+
+        # Test set operations with ints and floats promoted to their common type
+        x = rvec.RVec([1, 2, 3, 2])
+        y = rvec.RVec([2.0, 4.0])
+        self.assertEqual(x.union(y).to_list(), [1.0, 2.0, 3.0, 4.0])
+        self.assertEqual(x.intersect(y).to_list(), [2.0])
+        self.assertEqual(x.setdiff(y).to_list(), [1.0, 3.0])
+        self.assertEqual(x.setdiff([1, 3]).to_list(), [2])
+
+        # Test is_in and match_in
+        self.assertEqual(x.is_in(y).to_list(), [False, True, False, True])
+        self.assertEqual(x.match_in([3, 2, 2]).to_list(), [None, 1, 0, 1])
+        self.assertEqual(rvec.RVec([None, float("nan")]).match_in([float("nan"), None]).to_list(), [1, 0])
+        self.assertEqual(rvec.RVec(["b", None]).is_in(["a", "b"]).to_list(), [True, False])
+
+    def test_table(self):
+        r = rvec.RVec(["b", "a", None, "b", "c", "b", "a"])
+
+        # Test counts sorted by value, by count and with NA
+        t = r.table()
+        self.assertEqual(t.names, ["a", "b", "c"])
+        self.assertEqual(t.to_list(), [2, 3, 1])
+        t = r.value_counts()
+        self.assertEqual(t.names, ["b", "a", "c"])
+        self.assertEqual(t.to_list(), [3, 2, 1])
+        t = r.table(use_na=True)
+        self.assertEqual(t.names, ["a", "b", "c", None])
+        self.assertEqual(t.to_list(), [2, 3, 1, 1])
+        self.assertEqual(rvec.RVec([True, False, True]).table().names, ["FALSE", "TRUE"])
+
+        # Test two way tables
+        x = rvec.RVec([1, 1, 2, 2, 2])
+        y = rvec.RVec(["u", "v", "u", "u", None])
+        t = rvec.table(x, y)
+        self.assertEqual(t[1].names, ["u", "v"])
+        self.assertEqual(t[1].to_list(), [1, 1])
+        self.assertEqual(t[2].to_list(), [2, 0])
+        self.assertEqual(rvec.table(x, y, use_na=True)[2].to_list(), [2, 0, 1])
+        self.assertEqual(rvec.table(x).to_list(), [2, 3])
+        with self.assertRaises(ValueError):
+            rvec.table(x, rvec.RVec([1]))
+
+    def test_seq(self):
+        # Test from:to and steps
+        self.assertEqual(rvec.seq(1, 5).to_list(), [1, 2, 3, 4, 5])
+        self.assertEqual(rvec.seq(3, 1).to_list(), [3, 2, 1])
+        self.assertEqual(rvec.seq(5).to_list(), [1, 2, 3, 4, 5])
+        self.assertEqual(rvec.seq(0).to_list(), [1, 0])
+        self.assertEqual(rvec.seq(1.5, 3).to_list(), [1.5, 2.5])
+        self.assertEqual(rvec.seq(1, 10, by=3).to_list(), [1, 4, 7, 10])
+        self.assertEqual(rvec.seq(0, 1, by=0.25).to_list(), [0.0, 0.25, 0.5, 0.75, 1.0])
+        self.assertEqual(rvec.seq(0, 0.3, by=0.1).to_list(), [0.0, 0.1, 0.2, 0.3])
+        self.assertEqual(rvec.seq(10, 1, by=-4).to_list(), [10, 6, 2])
+
+        # Test length_out
+        self.assertEqual(rvec.seq(0, 1, length_out=3).to_list(), [0.0, 0.5, 1.0])
+        self.assertEqual(rvec.seq(5, by=2, length_out=3).to_list(), [5, 7, 9])
+        self.assertEqual(rvec.seq(to=10, length_out=3).to_list(), [8, 9, 10])
+        self.assertEqual(rvec.seq(length_out=2).to_list(), [1, 2])
+        self.assertEqual(rvec.seq_len(3).to_list(), [1, 2, 3])
+        self.assertEqual(rvec.seq_len(0).to_list(), [])
+        self.assertEqual(rvec.seq_along(["a", "b"]).to_list(), [1, 2])
+
+        with self.assertRaises(ValueError):
+            rvec.seq(1, 10, by=-1)
+        with self.assertRaises(ValueError):
+            rvec.seq(1, 10, by=1, length_out=3)
+
+    def test_rep(self):
+        self.assertEqual(rvec.rep([1, 2], times=2).to_list(), [1, 2, 1, 2])
+        self.assertEqual(rvec.rep([1, 2], each=2).to_list(), [1, 1, 2, 2])
+        self.assertEqual(rvec.rep([1, 2], times=[3, 1]).to_list(), [1, 1, 1, 2])
+        self.assertEqual(rvec.rep([1, 2], each=2, times=2).to_list(), [1, 1, 2, 2, 1, 1, 2, 2])
+        self.assertEqual(rvec.rep(["a", "b", "c"], length_out=5).to_list(), ["a", "b", "c", "a", "b"])
+        self.assertEqual(rvec.rep(rvec.RVec({"x": 1}), times=2).names, ["x", "x"])
+        self.assertEqual(rvec.rep(rvec.RVec([]), length_out=2).to_list(), [None, None])
+        with self.assertRaises(ValueError):
+            rvec.rep([1, 2], times=[1, 2, 3])
+        with self.assertRaises(ValueError):
+            rvec.rep([1, 2], times=-1)
+
+    def test_c(self):
+        # Test flattening and coercion to the common type
+        self.assertEqual(rvec.c(1, 2, 3).to_list(), [1, 2, 3])
+        self.assertEqual(rvec.c(1, [2, (3, 4)], rvec.RVec([5.5])).to_list(), [1.0, 2.0, 3.0, 4.0, 5.5])
+        self.assertEqual(rvec.c(True, 2).to_list(), [1, 2])
+        self.assertEqual(rvec.c(1, "a", None).to_list(), ["1", "a", None])
+        self.assertEqual(rvec.c([1, "a"]).to_list(), ["1", "a"])
+        self.assertEqual(len(rvec.c()), 0)
+
+        # Test names from RVecs, dicts and keywords
+        r = rvec.c(rvec.RVec({"a": 1}), 2, {"b": 3})
+        self.assertEqual(r.names, ["a", "", "b"])
+        self.assertEqual(r.to_list(), [1, 2, 3])
+        self.assertEqual(rvec.c(1, x=2, y=[3, 4]).names, ["", "x", "y1", "y2"])
+        self.assertEqual(rvec.c(z=rvec.RVec({"p": 1, "q": 2})).names, ["z.p", "z.q"])
+        self.assertIsNone(rvec.c(1, [2]).names)
+
+    def test_factor(self):
+        f = rvec.factor(["lo", "hi", None, "lo", "mid"])
+
+        # Test levels, labels and conversions
+        self.assertEqual(f.levels, ["hi", "lo", "mid"])
+        self.assertEqual(f.to_list(), ["lo", "hi", None, "lo", "mid"])
+        self.assertEqual(f.int().to_list(), [2, 1, None, 2, 3])
+        self.assertEqual(f.str().to_list(), ["lo", "hi", None, "lo", "mid"])
+        self.assertEqual(str(f), "[lo, hi, NA, lo, mid]")
+        self.assertIsNone(rvec.RVec([1]).levels)
+        self.assertEqual(rvec.factor([3, 1, 2, 1]).levels, ["1", "2", "3"])
+        g = rvec.factor(["a", "b", "c"], labels=["x", "y", "x"])
+        self.assertEqual(g.levels, ["x", "y"])
+        self.assertEqual(g.to_list(), ["x", "y", "x"])
+
+        # Test droplevels and relevel
+        f = rvec.factor(["lo", "hi", "lo"], levels=["lo", "mid", "hi"])
+        self.assertEqual(f.droplevels().levels, ["lo", "hi"])
+        self.assertEqual(f.relevel("hi").levels, ["hi", "lo", "mid"])
+        self.assertEqual(f.relevel("hi").to_list(), ["lo", "hi", "lo"])
+        with self.assertRaises(ValueError):
+            f.relevel("none")
+        self.assertEqual(f.table().names, ["lo", "mid", "hi"])
+        self.assertEqual(f.table().to_list(), [2, 0, 1])
+
+        # Test comparisons, ordered factors compare by level
+        o = rvec.factor(["lo", "hi", "mid"], levels=["lo", "mid", "hi"], ordered=True)
+        self.assertEqual((o > "lo").to_list(), [False, True, True])
+        self.assertEqual((o == "mid").to_list(), [False, False, True])
+        self.assertEqual(o.max().to_list(), ["hi"])
+        self.assertEqual(o.sort().to_list(), ["lo", "mid", "hi"])
+        self.assertEqual((f == "lo").to_list(), [True, False, True])
+        with self.assertWarns(UserWarning):
+            self.assertEqual((f < f).to_list(), [None, None, None])
+        with self.assertWarns(UserWarning):
+            self.assertEqual((rvec.factor(["b", "a"]) < "b").to_list(), [None, None])
+        with self.assertWarns(UserWarning):
+            self.assertEqual((rvec.factor(["b", "a"]) < rvec.factor(["a", "a"])).to_list(), [None, None])
+        with self.assertRaises(TypeError):
+            f + f
+        with self.assertRaises(TypeError):
+            f + 1
+        with self.assertRaises(TypeError):
+            "x" + f
+
+        # Test assignment and concatenation
+        f[0] = "mid"
+        self.assertEqual(f.to_list(), ["mid", "hi", "lo"])
+        with self.assertWarns(UserWarning):
+            f[1] = "new"
+        self.assertEqual(f.to_list(), ["mid", None, "lo"])
+        h = rvec.c(rvec.factor(["a"]), rvec.factor(["b"]))
+        self.assertEqual(h.levels, ["a", "b"])
+        self.assertEqual(rvec.c(rvec.factor(["a"]), "b").to_list(), ["a", "b"])
+
+    def test_date(self):
+        d = rvec.as_date(["2024-01-31", "2024-03-01", None])
+
+        # Test conversions to and from python dates
+        self.assertEqual(d.to_list(), [datetime.date(2024, 1, 31), datetime.date(2024, 3, 1), None])
+        self.assertEqual(str(d), "[2024-01-31, 2024-03-01, NA]")
+        self.assertEqual(rvec.RVec([datetime.date(1970, 1, 2)]).int().to_list(), [1])
+        self.assertEqual(rvec.as_date([0, 365]).str().to_list(), ["1970-01-01", "1971-01-01"])
+        self.assertEqual(rvec.as_date([2000000000]).is_na().to_list(), [True])
+        with self.assertRaises(ValueError):
+            (rvec.RVec([datetime.date(9999, 12, 31)]) + 1).to_list()
+        self.assertEqual(rvec.as_date(["31/01/2024"], format="%d/%m/%Y").to_list(), [datetime.date(2024, 1, 31)])
+        with self.assertWarns(UserWarning):
+            self.assertEqual(rvec.as_date(["not a date"]).to_list(), [None])
+        self.assertEqual(d.format("%d %b %Y").to_list(), ["31 Jan 2024", "01 Mar 2024", None])
+        with self.assertRaises(ValueError):
+            d.format("%Q")
+        with self.assertRaises(ValueError):
+            d.format("%H:%M")
+        with self.assertRaises(ValueError):
+            d.format("%z")
+
+        # Test arithmetic and comparisons
+        self.assertEqual((d + 1).to_list(), [datetime.date(2024, 2, 1), datetime.date(2024, 3, 2), None])
+        self.assertEqual((d - 31).str().to_list(), ["2023-12-31", "2024-01-30", None])
+        self.assertEqual((d + 200000000).is_na().to_list(), [True, True, True])
+        self.assertEqual((d[1] - d[0]).to_list(), [datetime.timedelta(days=30)])
+        self.assertEqual((d > datetime.date(2024, 2, 1)).to_list(), [False, True, None])
+        self.assertEqual(d.max(na_rm=True).to_list(), [datetime.date(2024, 3, 1)])
+        with self.assertRaises(TypeError):
+            d * 2
+        with self.assertRaises(TypeError):
+            d + d
+
+        # Test sequences of dates
+        jan31 = datetime.date(2024, 1, 31)
+        self.assertEqual(rvec.seq(jan31, length_out=3, by="month").str().to_list(), ["2024-01-31", "2024-02-29", "2024-03-31"])
+        self.assertEqual(rvec.seq(jan31, datetime.date(2024, 2, 14), by="week").str().to_list(), ["2024-01-31", "2024-02-07", "2024-02-14"])
+        self.assertEqual(rvec.seq(jan31, datetime.date(2024, 2, 2)).str().to_list(), ["2024-01-31", "2024-02-01", "2024-02-02"])
+        self.assertEqual(rvec.seq(jan31, length_out=2, by="-2 years").str().to_list(), ["2024-01-31", "2022-01-31"])
+        with self.assertRaises(ValueError):
+            rvec.seq(jan31, length_out=2, by="fortnight")
+
+    def test_datetime(self):
+        t = rvec.as_datetime(["2024-03-10T23:45:30.5+01:00", "2024-03-11 08:00", None])
+
+        # Test parsing, formatting and python datetimes
+        self.assertEqual(t.utc_offset, "+01:00")
+        self.assertEqual(str(t), "[2024-03-10T23:45:30.500+01:00, 2024-03-11T09:00:00+01:00, NA]")
+        plus1 = datetime.timezone(datetime.timedelta(hours=1))
+        self.assertEqual(t.to_list()[1], datetime.datetime(2024, 3, 11, 9, tzinfo=plus1))
+        self.assertEqual(t.with_offset("UTC").format("%H:%M").to_list(), ["22:45", "08:00", None])
+        self.assertEqual(rvec.as_datetime(["01/02/2024 10:00"], format="%d/%m/%Y %H:%M", offset="+02:00").format().to_list(), ["2024-02-01T10:00:00+02:00"])
+        minus5 = datetime.timezone(datetime.timedelta(hours=-5))
+        p = rvec.RVec([datetime.datetime(2024, 1, 1, 12, tzinfo=minus5), datetime.datetime(2024, 1, 1, 12)])
+        self.assertEqual(p.utc_offset, "-05:00")
+        self.assertEqual(p.to_list(), [datetime.datetime(2024, 1, 1, 12, tzinfo=minus5), datetime.datetime(2024, 1, 1, 7, tzinfo=minus5)])
+        self.assertEqual(rvec.as_datetime([0, 1.5]).float().to_list(), [0.0, 1.5])
+        with self.assertRaises(ValueError):
+            t.with_offset("nowhere")
+
+        # Test components in the vector's offset
+        self.assertEqual(t.hour().to_list(), [23, 9, None])
+        self.assertEqual(t.day().to_list(), [10, 11, None])
+        self.assertEqual(t.weekday().to_list(), [7, 1, None])
+        self.assertEqual(t.isoweek().to_list(), [10, 11, None])
+        self.assertEqual(t.second().to_list(), [30, 0, None])
+        self.assertEqual(rvec.as_date(t).str().to_list(), ["2024-03-10", "2024-03-11", None])
+        self.assertEqual(rvec.as_datetime(rvec.as_date(rvec.RVec([2000000000]))).to_list(), [None])
+        self.assertEqual((rvec.RVec([datetime.date(2020, 1, 1)]) + 200000000 == rvec.RVec([datetime.datetime(2020, 1, 1)])).to_list(), [None])
+
+        # Test truncating and rounding to units
+        self.assertEqual(t.trunc("hours").format("%d %H:%M").to_list(), ["10 23:00", "11 09:00", None])
+        self.assertEqual(t.round(units="days").format("%d %H:%M").to_list(), ["11 00:00", "11 00:00", None])
+        self.assertEqual(t.trunc("weeks").format("%d").to_list(), ["04", "11", None])
+        self.assertEqual(rvec.as_date(["2024-05-17"]).trunc("months").str().to_list(), ["2024-05-01"])
+        with self.assertRaises(ValueError):
+            t.trunc("fortnights")
+
+        # Test arithmetic and comparisons
+        self.assertEqual((t + 3600).hour().to_list(), [0, 10, None])
+        self.assertEqual((t[1] - t[0]).to_list(), [datetime.timedelta(seconds=33269.5)])
+        self.assertEqual((t[1] - t[0]).units, "hours")
+        self.assertEqual((p > "2024-01-01T13:00:00Z").to_list(), [True, False])
+        self.assertEqual(p.min().to_list(), [datetime.datetime(2024, 1, 1, 7, tzinfo=minus5)])
+        with self.assertRaises(TypeError):
+            t + t
+
+    def test_duration(self):
+        d = rvec.as_duration([90, 3600, None], units="mins")
+
+        # Test units and python timedeltas
+        self.assertEqual(d.units, "mins")
+        self.assertEqual(str(d), "[90 mins, 3600 mins, NA]")
+        self.assertEqual(d.to_list(), [datetime.timedelta(minutes=90), datetime.timedelta(hours=60), None])
+        d.units = "hours"
+        self.assertEqual(d.float().to_list(), [1.5, 60.0, None])
+        r = rvec.RVec([datetime.timedelta(days=2), datetime.timedelta(hours=36), datetime.timedelta(days=1)])
+        self.assertEqual(r.units, "days")
+        self.assertEqual(r.float().to_list(), [2.0, 1.5, 1.0])
+        self.assertEqual(rvec.as_duration(["1.5 hours", "30 mins"]).units, "mins")
+        self.assertEqual(rvec.as_duration(d, units="weeks").units, "weeks")
+        with self.assertRaises(ValueError):
+            d.units = "months"
+
+        # Test arithmetic with numbers in the duration's units and other durations
+        self.assertEqual((d + 1).float().to_list(), [2.5, 61.0, None])
+        self.assertEqual((d * 2).units, "hours")
+        self.assertEqual((d + r).float().to_list(), [49.5, 96.0, None])
+        self.assertEqual((r / rvec.as_duration([12], units="hours")).to_list(), [4.0, 3.0, 2.0])
+        self.assertEqual(d.sum(na_rm=True).to_list(), [datetime.timedelta(hours=61.5)])
+        self.assertEqual(d.mean(na_rm=True).units, "hours")
+        with self.assertRaises(TypeError):
+            d.prod()
+
+        # Test assigning durations to numbers and complex numbers to durations
+        x = rvec.RVec([1, 2])
+        x[0] = datetime.timedelta(days=1)
+        self.assertEqual(x.to_list(), [datetime.timedelta(days=1), datetime.timedelta(seconds=2)])
+        with self.assertRaises(TypeError):
+            rvec.as_duration([1, 2])[0] = 1j
+
+        # Test dates and datetimes with durations, and comparisons
+        t = rvec.as_datetime(["2024-01-01T00:00:00Z"])
+        self.assertEqual((t + d[0]).format("%H:%M").to_list(), ["01:30"])
+        self.assertEqual((rvec.as_date(["2024-01-01"]) + r[0]).str().to_list(), ["2024-01-03"])
+        self.assertEqual((d > 2).to_list(), [False, True, None])
+        self.assertEqual((d < r).to_list(), [True, False, None])
+        self.assertEqual((d == "90 mins").to_list(), [True, False, None])
+
+    def test_complex(self):
+        z = rvec.RVec([1+2j, -4, None])
+
+        # Test conversions to and from python complex numbers
+        self.assertEqual(z.to_list(), [1+2j, -4+0j, None])
+        self.assertEqual(str(z), "[1+2i, -4+0i, NA]")
+        self.assertEqual(rvec.RVec(["1+2i", "-2.5i", "3"]).complex().to_list(), [1+2j, -2.5j, 3+0j])
+        with self.assertWarns(UserWarning):
+            self.assertEqual(z.float().to_list(), [1.0, -4.0, None])
+
+        # Test arithmetic with promotion from ints and floats
+        self.assertEqual((z + 1).to_list(), [2+2j, -3+0j, None])
+        self.assertEqual((z * 2.5).to_list(), [2.5+5j, -10+0j, None])
+        self.assertEqual((z / 1j).to_list(), [2-1j, 4j, None])
+        self.assertEqual((rvec.RVec([1j]) ** 2).to_list(), [-1+0j])
+        self.assertEqual((-z).to_list(), [-1-2j, 4+0j, None])
+        self.assertEqual(z.sum(na_rm=True).to_list(), [-3+2j])
+        with self.assertRaises(TypeError):
+            z % 2
+        with self.assertRaises(TypeError):
+            z.max()
+
+        # Test the parts of complex numbers and comparisons
+        self.assertEqual(rvec.Re(z).to_list(), [1.0, -4.0, None])
+        self.assertEqual(rvec.Im(z).to_list(), [2.0, 0.0, None])
+        self.assertEqual(rvec.Mod(rvec.RVec([3+4j])).to_list(), [5.0])
+        self.assertAlmostEqual(rvec.Arg(rvec.RVec([-1])).to_list()[0], math.pi)
+        self.assertEqual(rvec.Conj(z).to_list(), [1-2j, -4+0j, None])
+        self.assertEqual((z == 1+2j).to_list(), [True, False, None])
+        with self.assertRaises(TypeError):
+            z < 1
+
+        # Test sqrt, exp and log of complex numbers
+        self.assertEqual(rvec.RVec([-4+0j]).sqrt().to_list(), [2j])
+        self.assertAlmostEqual(rvec.RVec([1j * math.pi]).exp().to_list()[0], -1)
+        self.assertAlmostEqual(rvec.RVec([-1+0j]).log().to_list()[0], 1j * math.pi)
+
 if __name__ == '__main__':
     unittest.main()
\ No newline at end of file